@@ -0,0 +1,74 @@
+use advent2022::day5::{Crane, Crate, Move, Stack};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+
+const CRATES: usize = 1_000_000;
+const CHUNK: usize = 100_000;
+const MOVES: usize = 200;
+
+fn labels(first: char) -> Stack {
+    let alphabet = (0..26)
+        .map(|i| Crate::new(&((first as u8 + i) as char).to_string()).unwrap())
+        .collect::<Vec<Crate>>();
+    alphabet.into_iter().cycle().take(CRATES).collect()
+}
+
+/// Two tall stacks, with every instruction moving a large chunk back and forth.
+fn stress_input() -> (Vec<Stack>, Vec<Move>) {
+    let stacks = vec![labels('A'), labels('a')];
+    let instructions = (0..MOVES)
+        .map(|i| Move {
+            count: CHUNK,
+            from: 1 + i % 2,
+            to: 2 - i % 2,
+        })
+        .collect();
+    (stacks, instructions)
+}
+
+/// The original approach: pop each crate onto a temporary stack, then push
+/// them back off it.
+fn move_one_at_a_time(stacks: &mut [Stack], instruction: &Move) {
+    let mut t_stack = Vec::new();
+    for _ in 0..instruction.count {
+        let v = stacks[instruction.from - 1].pop().unwrap();
+        t_stack.push(v);
+    }
+    while let Some(v) = t_stack.pop() {
+        stacks[instruction.to - 1].push(v)
+    }
+}
+
+fn bulk_moves(c: &mut Criterion) {
+    let (stacks, instructions) = stress_input();
+
+    let mut group = c.benchmark_group("bulk_moves");
+    group.sample_size(10);
+    group.bench_function("one_at_a_time", |b| {
+        b.iter_batched_ref(
+            || stacks.clone(),
+            |stacks| {
+                for instruction in &instructions {
+                    move_one_at_a_time(stacks, instruction);
+                }
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    for crane in [Crane::CrateMover9000, Crane::CrateMover9001] {
+        group.bench_function(format!("{:?}", crane), |b| {
+            b.iter_batched_ref(
+                || stacks.clone(),
+                |stacks| {
+                    for instruction in &instructions {
+                        crane.move_crates(stacks, instruction);
+                    }
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bulk_moves);
+criterion_main!(benches);
@@ -0,0 +1,96 @@
+use advent2022::input;
+use advent2022::logging;
+use advent2022::runner::{self, Part, DAYS};
+use std::process;
+
+const USAGE: &str = "Usage: aoc [-v|-vv] --day <N> [--part <1|2>] [--input <path|->]
+       aoc [-v|-vv] --all [--part <1|2>]
+
+Without --input, inputs are read from $AOC_INPUT_DIR/dayN/input.txt,
+$AOC_INPUT_DIR/dayN.txt, then the crate's src/dayN/input.txt.";
+
+#[derive(Debug, Default)]
+struct Args {
+    day: Option<u8>,
+    part: Option<Part>,
+    input: Option<String>,
+    all: bool,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+        let mut out = Args::default();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--day" | "-d" => {
+                    let value = args.next().ok_or("--day needs a value")?;
+                    let day = value
+                        .parse::<u8>()
+                        .map_err(|_| format!("'{}' is not a valid day", value))?;
+                    out.day = Some(day);
+                }
+                "--part" | "-p" => {
+                    let value = args.next().ok_or("--part needs a value")?;
+                    let part = Part::from_number(&value)
+                        .ok_or_else(|| format!("'{}' is not a valid part", value))?;
+                    out.part = Some(part);
+                }
+                "--input" | "-i" => {
+                    out.input = Some(args.next().ok_or("--input needs a value")?);
+                }
+                "--all" | "-a" => out.all = true,
+                "--help" | "-h" => {
+                    println!("{}", USAGE);
+                    process::exit(0);
+                }
+                _ => return Err(format!("unexpected argument '{}'", arg)),
+            }
+        }
+
+        match (out.all, out.day, &out.input) {
+            (true, Some(_), _) => Err("--all and --day can't be combined".to_owned()),
+            (true, _, Some(_)) => Err("--input needs a single --day".to_owned()),
+            (false, None, _) => Err("one of --day or --all is required".to_owned()),
+            _ => Ok(out),
+        }
+    }
+}
+
+fn run(args: &Args) -> Result<(), String> {
+    let days = match args.day {
+        Some(day) => vec![day],
+        None => DAYS.to_vec(),
+    };
+    let parts = match args.part {
+        Some(part) => vec![part],
+        None => Part::BOTH.to_vec(),
+    };
+
+    for day in days {
+        if !DAYS.contains(&day) {
+            return Err(format!("error: Day {} hasn't been solved", day));
+        }
+        let input =
+            input::load_input(day, args.input.as_deref()).map_err(|e| format!("error: {}", e))?;
+        let result = runner::run_day(day, &input, &parts)
+            .ok_or_else(|| format!("error: Day {} hasn't been solved", day))?
+            .map_err(|e| e.render())?;
+        print!("{}", result);
+    }
+    Ok(())
+}
+
+fn main() {
+    let args = match Args::parse(logging::init_from_args().into_iter()) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+
+    if let Err(e) = run(&args) {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,380 @@
+use super::Elf;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::error::Error;
+use std::fmt;
+
+/// Most items `balance` will search exhaustively before falling back to the
+/// greedy method.
+pub const EXACT_MAX_ITEMS: usize = 20;
+
+/// Most assignments the exhaustive search will try before settling for the
+/// greedy result.
+pub const EXACT_MAX_NODES: usize = 1_000_000;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Method {
+    /// Branch and bound over every assignment, giving the smallest maximum
+    Exact,
+    /// Longest processing time first: each item, biggest first, goes to the
+    /// least loaded elf. Never more than 4/3 of the smallest maximum.
+    Greedy,
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Method::Exact => write!(f, "exact"),
+            Method::Greedy => write!(f, "greedy"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum BalanceError {
+    NoElves,
+}
+
+impl fmt::Display for BalanceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BalanceError::NoElves => write!(f, "items can only be shared among 1 or more elves"),
+        }
+    }
+}
+
+impl Error for BalanceError {}
+
+/// Smallest and largest load in a set of elves.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Spread {
+    pub min: u128,
+    pub max: u128,
+}
+
+impl Spread {
+    /// Both are 0 if there are no loads.
+    fn of(loads: impl IntoIterator<Item = u128>) -> Spread {
+        loads
+            .into_iter()
+            .map(|load| Spread {
+                min: load,
+                max: load,
+            })
+            .reduce(|a, b| Spread {
+                min: a.min.min(b.min),
+                max: a.max.max(b.max),
+            })
+            .unwrap_or(Spread { min: 0, max: 0 })
+    }
+
+    pub fn range(&self) -> u128 {
+        self.max.saturating_sub(self.min)
+    }
+}
+
+/// Every food item shared out again among `elves` elves.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Balance {
+    pub method: Method,
+    pub elves: usize,
+    /// The items each elf ends up carrying, biggest first. Only the first
+    /// `elves.min(items)` elves are listed, as any more carry nothing.
+    pub bins: Vec<Vec<u64>>,
+    pub loads: Vec<u128>,
+    pub balanced: Spread,
+    pub original: Spread,
+    /// No assignment can have a smaller maximum than this
+    pub lower_bound: u128,
+}
+
+/// Shares the elves' items among `elves` elves, searching exhaustively when
+/// there are at most `EXACT_MAX_ITEMS` items and the search finishes within
+/// `EXACT_MAX_NODES` assignments.
+pub fn balance(inventory: &[Elf], elves: usize) -> Result<Balance, BalanceError> {
+    let items = inventory.iter().map(|elf| elf.items.len()).sum::<usize>();
+    let method = match items <= EXACT_MAX_ITEMS {
+        true => Method::Exact,
+        false => Method::Greedy,
+    };
+    balance_with(inventory, elves, method)
+}
+
+/// An exact search that runs out of nodes gives the greedy result instead,
+/// and says so in `method`.
+pub fn balance_with(
+    inventory: &[Elf],
+    elves: usize,
+    mut method: Method,
+) -> Result<Balance, BalanceError> {
+    if elves == 0 {
+        return Err(BalanceError::NoElves);
+    }
+
+    let mut items = inventory
+        .iter()
+        .flat_map(|elf| elf.items.iter().copied())
+        .collect::<Vec<u64>>();
+    items.sort_unstable_by(|a, b| b.cmp(a));
+
+    let total = items.iter().map(|&c| u128::from(c)).sum::<u128>();
+    let biggest = items.first().map_or(0, |&c| u128::from(c));
+    let lower_bound = total.div_ceil(elves as u128).max(biggest);
+
+    // Elves beyond one per item would only ever get empty loads
+    let used = elves.min(items.len());
+    let mut assignment = greedy(&items, used);
+    if method == Method::Exact {
+        match exact(&items, used, &assignment, lower_bound) {
+            Some(best) => assignment = best,
+            None => method = Method::Greedy,
+        }
+    }
+
+    let mut bins = vec![Vec::new(); used];
+    for (&item, &bin) in items.iter().zip(&assignment) {
+        bins[bin].push(item);
+    }
+    let loads = bins
+        .iter()
+        .map(|bin| bin.iter().map(|&c| u128::from(c)).sum())
+        .collect::<Vec<u128>>();
+
+    let idle = (elves > used).then_some(0);
+    Ok(Balance {
+        method,
+        elves,
+        balanced: Spread::of(loads.iter().copied().chain(idle)),
+        original: Spread::of(inventory.iter().map(|elf| u128::from(elf.total))),
+        bins,
+        loads,
+        lower_bound,
+    })
+}
+
+/// The elf each of `items`, sorted biggest first, goes to. Ties go to the
+/// elf listed first.
+fn greedy(items: &[u64], elves: usize) -> Vec<usize> {
+    let mut loads = (0..elves)
+        .map(|bin| Reverse((0u128, bin)))
+        .collect::<BinaryHeap<_>>();
+    items
+        .iter()
+        .map(|&item| {
+            let Reverse((load, bin)) = loads.pop().expect("there is at least one elf");
+            loads.push(Reverse((load + u128::from(item), bin)));
+            bin
+        })
+        .collect()
+}
+
+/// Improves on `best` until nothing better exists or it meets `lower_bound`.
+/// Returns `None` if that takes more than `EXACT_MAX_NODES` assignments.
+fn exact(items: &[u64], elves: usize, best: &[usize], lower_bound: u128) -> Option<Vec<usize>> {
+    struct Search<'a> {
+        items: &'a [u64],
+        lower_bound: u128,
+        nodes: usize,
+        loads: Vec<u128>,
+        assignment: Vec<usize>,
+        best: Vec<usize>,
+        best_max: u128,
+    }
+
+    impl Search<'_> {
+        fn place(&mut self, i: usize) {
+            if self.best_max == self.lower_bound || self.nodes > EXACT_MAX_NODES {
+                return;
+            }
+            self.nodes += 1;
+            let Some(&item) = self.items.get(i) else {
+                // Every load is below the best maximum, or it would have been pruned
+                self.best_max = self.loads.iter().copied().max().unwrap_or(0);
+                self.best = self.assignment.clone();
+                return;
+            };
+
+            for bin in 0..self.loads.len() {
+                // Elves carrying the same load are interchangeable
+                if self.loads[..bin].contains(&self.loads[bin]) {
+                    continue;
+                }
+                let load = self.loads[bin] + u128::from(item);
+                if load >= self.best_max {
+                    continue;
+                }
+                self.loads[bin] = load;
+                self.assignment[i] = bin;
+                self.place(i + 1);
+                self.loads[bin] -= u128::from(item);
+            }
+        }
+    }
+
+    let best_max = loads_of(items, elves, best).into_iter().max().unwrap_or(0);
+    let mut search = Search {
+        items,
+        lower_bound,
+        nodes: 0,
+        loads: vec![0; elves],
+        assignment: vec![0; items.len()],
+        best: best.to_vec(),
+        best_max,
+    };
+    search.place(0);
+    (search.nodes <= EXACT_MAX_NODES).then_some(search.best)
+}
+
+fn loads_of(items: &[u64], elves: usize, assignment: &[usize]) -> Vec<u128> {
+    let mut loads = vec![0; elves];
+    for (&item, &bin) in items.iter().zip(assignment) {
+        loads[bin] += u128::from(item);
+    }
+    loads
+}
+
+impl fmt::Display for Balance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} items shared among {} elves ({})",
+            self.bins.iter().map(Vec::len).sum::<usize>(),
+            self.elves,
+            self.method
+        )?;
+        writeln!(f, "            max load  min load    spread")?;
+        for (label, spread) in [("original", self.original), ("balanced", self.balanced)] {
+            writeln!(
+                f,
+                "{:<10} {:>9} {:>9} {:>9}",
+                label,
+                spread.max,
+                spread.min,
+                spread.range()
+            )?;
+        }
+        write!(f, "lower bound {:>8}", self.lower_bound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inventory(elves: &[&[u64]]) -> Vec<Elf> {
+        elves
+            .iter()
+            .enumerate()
+            .map(|(i, items)| Elf::new(i + 1, items.to_vec()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_exact_beats_greedy() {
+        let elves = inventory(&[&[3, 3], &[2, 2, 2]]);
+        let greedy = balance_with(&elves, 2, Method::Greedy).unwrap();
+        assert_eq!(greedy.balanced.max, 7);
+
+        let exact = balance(&elves, 2).unwrap();
+        assert_eq!(exact.method, Method::Exact);
+        assert_eq!(exact.bins, vec![vec![3, 3], vec![2, 2, 2]]);
+        assert_eq!(exact.balanced, Spread { min: 6, max: 6 });
+        assert_eq!(exact.lower_bound, 6);
+    }
+
+    #[test]
+    fn test_example() {
+        let elves = inventory(&[
+            &[1000, 2000, 3000],
+            &[4000],
+            &[5000, 6000],
+            &[7000, 8000, 9000],
+            &[10000],
+        ]);
+        let balanced = balance(&elves, 5).unwrap();
+        assert_eq!(
+            balanced.original,
+            Spread {
+                min: 4000,
+                max: 24000
+            }
+        );
+        assert_eq!(
+            balanced.balanced,
+            Spread {
+                min: 11000,
+                max: 11000
+            }
+        );
+        assert_eq!(balanced.loads.iter().sum::<u128>(), 55000);
+
+        let fewer = balance(&elves, 3).unwrap();
+        assert_eq!(fewer.lower_bound, 18334);
+        assert_eq!(fewer.balanced.max, 19000);
+    }
+
+    #[test]
+    fn test_exact_matches_brute_force() {
+        let items: &[u64] = &[17, 11, 9, 8, 8, 5, 4, 3, 2, 1];
+        for elves in 1..=4usize {
+            let mut best = u128::MAX;
+            for code in 0..elves.pow(items.len() as u32) {
+                let mut loads = vec![0u128; elves];
+                let mut code = code;
+                for &item in items {
+                    loads[code % elves] += u128::from(item);
+                    code /= elves;
+                }
+                best = best.min(*loads.iter().max().unwrap());
+            }
+            let balanced = balance(&inventory(&[items]), elves).unwrap();
+            assert_eq!(balanced.balanced.max, best, "{} elves", elves);
+        }
+    }
+
+    #[test]
+    fn test_more_elves_than_items() {
+        let elves = inventory(&[&[5, 3], &[4]]);
+        let balanced = balance(&elves, 100_000_000_000_000).unwrap();
+        assert_eq!(balanced.method, Method::Exact);
+        assert_eq!(balanced.elves, 100_000_000_000_000);
+        assert_eq!(balanced.bins, vec![vec![5], vec![4], vec![3]]);
+        assert_eq!(balanced.balanced, Spread { min: 0, max: 5 });
+        assert_eq!(balanced.lower_bound, 5);
+        assert!(balanced
+            .to_string()
+            .starts_with("3 items shared among 100000000000000 elves (exact)"));
+
+        let greedy = balance_with(&elves, usize::MAX, Method::Greedy).unwrap();
+        assert_eq!(greedy.bins, balanced.bins);
+    }
+
+    #[test]
+    fn test_exact_node_budget() {
+        // Near-equal items the search can't prove it has placed best
+        let items = (0..20)
+            .map(|i| 1_000_000 + i * 7919 % 100_003)
+            .collect::<Vec<u64>>();
+        let elves = inventory(&[&items]);
+        let balanced = balance(&elves, 9).unwrap();
+        assert_eq!(balanced.method, Method::Greedy);
+        assert_eq!(balanced, balance_with(&elves, 9, Method::Greedy).unwrap());
+    }
+
+    #[test]
+    fn test_greedy_for_many_items() {
+        let items = (1..=100).collect::<Vec<u64>>();
+        let balanced = balance(&inventory(&[&items]), 7).unwrap();
+        assert_eq!(balanced.method, Method::Greedy);
+        assert_eq!(balanced.lower_bound, 722);
+        assert!(balanced.balanced.max * 3 <= balanced.lower_bound * 4);
+        assert_eq!(balanced.bins.iter().map(Vec::len).sum::<usize>(), 100);
+
+        assert_eq!(balance(&[], 0), Err(BalanceError::NoElves));
+        let empty = balance(&[], 2).unwrap();
+        assert_eq!(empty.elves, 2);
+        assert!(empty.loads.is_empty());
+        assert_eq!(empty.balanced, Spread { min: 0, max: 0 });
+        assert_eq!(empty.original, Spread { min: 0, max: 0 });
+    }
+}
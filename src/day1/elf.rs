@@ -0,0 +1,244 @@
+use super::top::TopK;
+use super::Day1;
+use crate::input;
+use crate::{ParseError, Solution};
+use std::cmp::{Ordering, Reverse};
+use std::fmt;
+use std::num::IntErrorKind;
+
+/// One elf's inventory. `index` is the elf's position in the input, counting
+/// from 1.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Elf {
+    pub index: usize,
+    pub items: Vec<u64>,
+    pub total: u64,
+}
+
+impl Elf {
+    /// Returns `None` if the items add up to more than a `u64` holds.
+    pub fn new(index: usize, items: Vec<u64>) -> Option<Elf> {
+        let total = items
+            .iter()
+            .try_fold(0u64, |total, &c| total.checked_add(c))?;
+        Some(Elf {
+            index,
+            items,
+            total,
+        })
+    }
+
+    /// Sorts elves carrying more first, and elves carrying the same by
+    /// their position in the input.
+    fn rank_key(&self) -> (u64, Reverse<usize>) {
+        (self.total, Reverse(self.index))
+    }
+}
+
+/// Each elf in turn, read a line at a time without collecting the input.
+/// Stops after the first line that isn't a number of calories, or that takes
+/// an elf's total past `u64::MAX`.
+pub fn elves(input: &str) -> impl Iterator<Item = Result<Elf, ParseError>> + '_ {
+    let mut lines = input::lines(input).peekable();
+    let mut index = 0;
+    let mut failed = false;
+    std::iter::from_fn(move || {
+        while lines.next_if(|(_, s)| s.is_empty()).is_some() {}
+        if failed || lines.peek().is_none() {
+            return None;
+        }
+
+        index += 1;
+        let mut elf = Elf {
+            index,
+            items: Vec::new(),
+            total: 0,
+        };
+        while let Some((number, s)) = lines.next_if(|(_, s)| !s.is_empty()) {
+            let item = calories(s).and_then(|c| match elf.total.checked_add(c) {
+                Some(total) => {
+                    elf.total = total;
+                    Ok(c)
+                }
+                None => Err(ParseError::new(
+                    Day1::DAY,
+                    s,
+                    s,
+                    format!("elf {} carries more than {} calories", index, u64::MAX),
+                )),
+            });
+            match item {
+                Ok(c) => elf.items.push(c),
+                Err(e) => {
+                    failed = true;
+                    return Some(Err(e.at_line(number)));
+                }
+            }
+        }
+        Some(Ok(elf))
+    })
+}
+
+fn calories(line: &str) -> Result<u64, ParseError> {
+    line.parse::<u64>().map_err(|e| {
+        let message = match e.kind() {
+            IntErrorKind::PosOverflow => format!("'{}' is more calories than can be counted", line),
+            _ => format!("'{}' is not a number of calories", line),
+        };
+        ParseError::new(Day1::DAY, line, line, message)
+    })
+}
+
+/// Orders elves by `Elf::rank_key`, for `TopK`.
+#[derive(Debug, PartialEq, Eq)]
+struct ByRank(Elf);
+
+impl Ord for ByRank {
+    fn cmp(&self, other: &ByRank) -> Ordering {
+        self.0.rank_key().cmp(&other.0.rank_key())
+    }
+}
+
+impl PartialOrd for ByRank {
+    fn partial_cmp(&self, other: &ByRank) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The elves carrying the most, best first, alongside totals over every elf.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Ranking {
+    pub rows: Vec<Elf>,
+    pub elves: usize,
+    pub grand_total: u128,
+}
+
+impl Ranking {
+    /// Keeps the top `k` of `elves`. Elves carrying the same total are
+    /// ranked by their position in the input.
+    pub fn new(elves: impl IntoIterator<Item = Elf>, k: usize) -> Ranking {
+        let mut top = TopK::new(k);
+        let mut count = 0;
+        let mut grand_total = 0;
+        for elf in elves {
+            count += 1;
+            grand_total += u128::from(elf.total);
+            top.push(ByRank(elf));
+        }
+        Ranking {
+            rows: top.into_sorted_vec().into_iter().map(|r| r.0).collect(),
+            elves: count,
+            grand_total,
+        }
+    }
+
+    /// Percentage of all calories carried by `elf`.
+    pub fn share(&self, elf: &Elf) -> f64 {
+        match self.grand_total {
+            0 => 0.0,
+            grand_total => 100.0 * elf.total as f64 / grand_total as f64,
+        }
+    }
+}
+
+impl fmt::Display for Ranking {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "rank    elf  items     total   share")?;
+        for (rank, elf) in self.rows.iter().enumerate() {
+            write!(
+                f,
+                "\n{:>4}  {:>5}  {:>5}  {:>8}  {:>5.1}%",
+                rank + 1,
+                elf.index,
+                elf.items.len(),
+                elf.total,
+                self.share(elf)
+            )?;
+        }
+        write!(
+            f,
+            "\n{} elves carry {} calories",
+            self.elves, self.grand_total
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(input: &str) -> Vec<Elf> {
+        elves(input).collect::<Result<_, _>>().unwrap()
+    }
+
+    #[test]
+    fn test_ranking_ties() {
+        let input = "300\n\n100\n200\n\n50\n\n150\n150\n\n300";
+        let ranking = Ranking::new(parsed(input), 3);
+        let ranked = ranking
+            .rows
+            .iter()
+            .map(|elf| (elf.index, elf.total))
+            .collect::<Vec<_>>();
+        assert_eq!(ranked, vec![(1, 300), (2, 300), (4, 300)]);
+        assert_eq!(ranking.elves, 5);
+        assert_eq!(ranking.grand_total, 1250);
+        assert_eq!(ranking.rows[1].items, vec![100, 200]);
+        assert_eq!(ranking.share(&ranking.rows[0]), 24.0);
+    }
+
+    #[test]
+    fn test_ranking_table() {
+        let ranking = Ranking::new(parsed("1000\n2000\n\n4000\n\n5000"), 2);
+        assert_eq!(
+            ranking.to_string(),
+            "rank    elf  items     total   share
+   1      3      1      5000   41.7%
+   2      2      1      4000   33.3%
+3 elves carry 12000 calories"
+        );
+
+        let empty = Ranking::new(parsed(""), 3);
+        assert!(empty.rows.is_empty());
+        assert_eq!(empty.grand_total, 0);
+    }
+
+    #[test]
+    fn test_bad_lines() {
+        let err = elves("100\n\n200\n2x0\n\n300")
+            .collect::<Result<Vec<Elf>, _>>()
+            .unwrap_err();
+        assert_eq!((err.line, err.column, err.width), (4, 1, 3));
+        assert_eq!(err.message, "'2x0' is not a number of calories");
+
+        let err = elves("1\n\n99999999999999999999")
+            .collect::<Result<Vec<Elf>, _>>()
+            .unwrap_err();
+        assert_eq!(err.line, 3);
+        assert!(err.message.contains("more calories than can be counted"));
+
+        let mut iter = elves("1\n\nnope\n\n2");
+        assert_eq!(iter.next(), Some(Ok(Elf::new(1, vec![1]).unwrap())));
+        assert!(matches!(iter.next(), Some(Err(_))));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn test_overflow() {
+        let big = u64::MAX / 2 + 1;
+        let err = elves(&format!("5\n\n{}\n{}", big, big))
+            .collect::<Result<Vec<Elf>, _>>()
+            .unwrap_err();
+        assert_eq!(err.line, 4);
+        assert_eq!(
+            err.message,
+            format!("elf 2 carries more than {} calories", u64::MAX)
+        );
+        assert_eq!(Elf::new(1, vec![big, big]), None);
+
+        let huge = parsed(&format!("{}\n\n{}", u64::MAX, u64::MAX));
+        let ranking = Ranking::new(huge, 2);
+        assert_eq!(ranking.grand_total, 2 * u128::from(u64::MAX));
+        assert_eq!(ranking.share(&ranking.rows[0]), 50.0);
+    }
+}
@@ -1,33 +1,116 @@
-use std::fs;
+use advent2022::day1::{balance, elves, CalorieStats, Day1, Ranking};
+use advent2022::input;
+use advent2022::logging;
+use advent2022::Solution;
+use std::process;
 
-fn load_input_file() -> String {
-    fs::read_to_string("src/day1/input.txt").expect("Failed to read input file")
-}
+const USAGE: &str = "Usage: day1 [-v|-vv] [--top <K>] [input]
+       day1 [-v|-vv] --stats [--json] [--buckets <N>] [input]
+       day1 [-v|-vv] --balance [--elves <N>] [input]";
 
-fn sum_top_three_elves(input: String) -> u32 {
-    let strings = input.split("\n\n");
-    let strings = strings.map(|s| s.split('\n'));
+struct Args {
+    input: Option<String>,
+    top: Option<usize>,
+    stats: bool,
+    json: bool,
+    buckets: usize,
+    balance: bool,
+    elves: Option<usize>,
+}
 
-    let mut elf_cals: Vec<u32> = Vec::default();
+fn usage_error(message: &str) -> ! {
+    eprintln!("error: {}\n\n{}", message, USAGE);
+    process::exit(2);
+}
 
-    for string in strings {
-        let mut this_elf_cals = 0;
-        for s in string {
-            match s.parse::<u32>() {
-                Ok(v) => this_elf_cals += v,
-                Err(_) => eprintln!("ruh roh"),
+fn parse_args() -> Args {
+    let mut args = Args {
+        input: None,
+        top: None,
+        stats: false,
+        json: false,
+        buckets: 10,
+        balance: false,
+        elves: None,
+    };
+    let mut argv = logging::init_from_args().into_iter();
+    while let Some(arg) = argv.next() {
+        match arg.as_str() {
+            "--top" => match argv.next().and_then(|k| k.parse().ok()) {
+                Some(k) => args.top = Some(k),
+                None => usage_error("--top needs a number"),
+            },
+            "--stats" => args.stats = true,
+            "--json" => args.json = true,
+            "--buckets" => match argv.next().and_then(|n| n.parse().ok()) {
+                Some(n) if n > 0 => args.buckets = n,
+                _ => usage_error("--buckets needs a number above 0"),
+            },
+            "--balance" => args.balance = true,
+            "--elves" => match argv.next().and_then(|n| n.parse().ok()) {
+                Some(n) => args.elves = Some(n),
+                None => usage_error("--elves needs a number"),
+            },
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                process::exit(0);
             }
+            _ => args.input = Some(arg),
         }
-        elf_cals.push(this_elf_cals);
     }
-    elf_cals.sort();
-    elf_cals.reverse();
-    let v: u32 = elf_cals[..3].iter().sum();
-    v
+    args
+}
+
+fn load_input_file(arg: Option<&str>) -> String {
+    input::load_input(Day1::DAY, arg).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(1);
+    })
 }
 
 fn main() {
-    let input_str = load_input_file();
-    let best_elf = sum_top_three_elves(input_str);
-    println!("{:?}", best_elf);
+    let args = parse_args();
+    let input_str = load_input_file(args.input.as_deref());
+
+    if let Some(k) = args.top {
+        let elves = elves(&input_str).map(|elf| {
+            elf.unwrap_or_else(|e| {
+                eprintln!("{}", e.render());
+                process::exit(1);
+            })
+        });
+        println!("{}", Ranking::new(elves, k));
+        return;
+    }
+
+    let elves = Day1::parse(&input_str).unwrap_or_else(|e| {
+        eprintln!("{}", e.render());
+        process::exit(1);
+    });
+
+    if args.stats {
+        let Some(stats) = CalorieStats::new(&elves, args.buckets) else {
+            eprintln!("error: no elves in the input");
+            process::exit(1);
+        };
+        match args.json {
+            true => println!("{}", stats.to_json()),
+            false => println!("{}", stats),
+        }
+        return;
+    }
+
+    if args.balance {
+        match balance(&elves, args.elves.unwrap_or(elves.len())) {
+            Ok(balanced) => println!("{}", balanced),
+            Err(e) => {
+                eprintln!("error: {}", e);
+                process::exit(1);
+            }
+        }
+        return;
+    }
+
+    println!("{:?}", Day1::part1(&elves));
+    println!("{:?}", Day1::part2(&elves));
 }
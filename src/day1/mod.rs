@@ -0,0 +1,125 @@
+use crate::{ParseError, Solution};
+use tracing::debug;
+
+mod balance;
+mod elf;
+mod stats;
+mod top;
+
+pub use balance::{
+    balance, balance_with, Balance, BalanceError, Method, Spread, EXACT_MAX_ITEMS, EXACT_MAX_NODES,
+};
+pub use elf::{elves, Elf, Ranking};
+pub use stats::{Bucket, CalorieStats, Outlier, Percentile, PERCENTILES};
+pub use top::{top_k, TopK};
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u8 = 1;
+
+    type Input = Vec<Elf>;
+    type Answer1 = u128;
+    type Answer2 = u128;
+
+    fn parse(input: &str) -> Result<Vec<Elf>, ParseError> {
+        let elves = elves(input).collect::<Result<Vec<Elf>, _>>()?;
+        debug!(elves = elves.len(), "parsed calories");
+        Ok(elves)
+    }
+
+    fn part1(elves: &Vec<Elf>) -> u128 {
+        sum_top_elves(elves.iter().map(|elf| elf.total), 1)
+    }
+
+    fn part2(elves: &Vec<Elf>) -> u128 {
+        sum_top_elves(elves.iter().map(|elf| elf.total), 3)
+    }
+}
+
+/// Each elf's total, read a line at a time without collecting the input.
+pub fn elf_totals(input: &str) -> impl Iterator<Item = Result<u64, ParseError>> + '_ {
+    elves(input).map(|elf| elf.map(|elf| elf.total))
+}
+
+/// The `k` biggest elf totals, biggest first. There are fewer than `k` if
+/// there are fewer elves.
+pub fn top_elves(elf_cals: impl IntoIterator<Item = u64>, k: usize) -> Vec<u64> {
+    top_k(elf_cals, k)
+}
+
+/// Calories carried by the `k` elves carrying the most. Summed as a `u128`,
+/// which can't overflow however many elves there are.
+pub fn sum_top_elves(elf_cals: impl IntoIterator<Item = u64>, k: usize) -> u128 {
+    top_elves(elf_cals, k).into_iter().map(u128::from).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "1000
+2000
+3000
+
+4000
+
+5000
+6000
+
+7000
+8000
+9000
+
+10000";
+
+    #[test]
+    fn test_elf_calories() {
+        let elf_cals = elf_totals(EXAMPLE).collect::<Result<Vec<u64>, _>>();
+        assert_eq!(elf_cals, Ok(vec![6000, 4000, 11000, 24000, 10000]));
+
+        let elves = Day1::parse(EXAMPLE).unwrap();
+        assert_eq!(elves[3], Elf::new(4, vec![7000, 8000, 9000]).unwrap());
+    }
+
+    #[test]
+    fn test_example() {
+        // Example from AoC problem page
+        // https://adventofcode.com/2022/day/1
+        let elf_cals = Day1::parse(EXAMPLE).unwrap();
+        assert_eq!(Day1::part1(&elf_cals), 24000);
+        assert_eq!(Day1::part2(&elf_cals), 45000);
+    }
+
+    #[test]
+    fn test_top_elves() {
+        let totals = || elf_totals(EXAMPLE).map(Result::unwrap);
+        assert_eq!(top_elves(totals(), 2), vec![24000, 11000]);
+        assert_eq!(sum_top_elves(totals(), 5), 55000);
+        assert_eq!(sum_top_elves(totals(), 0), 0);
+
+        let few = Day1::parse("100\n\n200").unwrap();
+        assert_eq!(Day1::part2(&few), 300);
+        assert_eq!(Day1::part1(&Day1::parse("").unwrap()), 0);
+    }
+
+    #[test]
+    fn test_large_totals() {
+        let input = format!("{}\n\n{}\n\n{}", u64::MAX, u64::MAX, u64::MAX);
+        let elves = Day1::parse(&input).unwrap();
+        assert_eq!(Day1::part1(&elves), u128::from(u64::MAX));
+        assert_eq!(Day1::part2(&elves), 3 * u128::from(u64::MAX));
+
+        let err = Day1::parse("100\n\n1O0").unwrap_err();
+        assert_eq!(err.line, 3);
+    }
+
+    #[test]
+    fn test_windows_line_endings() {
+        let input = EXAMPLE.replace('\n', "\r\n") + "\r\n";
+        assert_eq!(Day1::parse(&input), Day1::parse(EXAMPLE));
+
+        let input = EXAMPLE.to_owned() + "\n\n";
+        assert_eq!(Day1::parse(&input), Day1::parse(EXAMPLE));
+    }
+}
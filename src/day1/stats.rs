@@ -0,0 +1,281 @@
+use super::Elf;
+use serde::Serialize;
+use std::fmt;
+
+/// Percentiles reported by `CalorieStats::new`.
+pub const PERCENTILES: [u8; 6] = [10, 25, 50, 75, 90, 99];
+
+/// Longest bar drawn in the text histogram.
+const BAR_WIDTH: usize = 40;
+
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct Percentile {
+    pub p: u8,
+    pub value: f64,
+}
+
+/// Elves whose totals fall in `start..=end`.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+pub struct Bucket {
+    pub start: u64,
+    pub end: u64,
+    pub elves: usize,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+pub struct Outlier {
+    pub index: usize,
+    pub total: u64,
+}
+
+/// Summary of the elves' totals. Percentiles interpolate between the
+/// nearest totals, the standard deviation is over every elf rather than a
+/// sample, and outliers lie more than 1.5 interquartile ranges outside the
+/// middle half.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct CalorieStats {
+    pub elves: usize,
+    pub total: u128,
+    pub min: u64,
+    pub max: u64,
+    pub mean: f64,
+    pub median: f64,
+    pub std_dev: f64,
+    pub percentiles: Vec<Percentile>,
+    pub histogram: Vec<Bucket>,
+    pub outliers: Vec<Outlier>,
+}
+
+impl CalorieStats {
+    /// Returns `None` if there are no elves. The histogram splits the range
+    /// of totals into at most `buckets` equal buckets.
+    pub fn new(elves: &[Elf], buckets: usize) -> Option<CalorieStats> {
+        let mut sorted = elves.iter().map(|elf| elf.total).collect::<Vec<u64>>();
+        sorted.sort_unstable();
+        let (&min, &max) = (sorted.first()?, sorted.last()?);
+
+        let n = sorted.len() as f64;
+        let total = sorted.iter().map(|&t| u128::from(t)).sum::<u128>();
+        let mean = total as f64 / n;
+        let variance = sorted
+            .iter()
+            .map(|&t| (t as f64 - mean).powi(2))
+            .sum::<f64>()
+            / n;
+
+        let (q1, q3) = (percentile(&sorted, 25.0), percentile(&sorted, 75.0));
+        let fence = 1.5 * (q3 - q1);
+        let outliers = elves
+            .iter()
+            .filter(|elf| {
+                let total = elf.total as f64;
+                total < q1 - fence || total > q3 + fence
+            })
+            .map(|elf| Outlier {
+                index: elf.index,
+                total: elf.total,
+            })
+            .collect();
+
+        Some(CalorieStats {
+            elves: sorted.len(),
+            total,
+            min,
+            max,
+            mean,
+            median: percentile(&sorted, 50.0),
+            std_dev: variance.sqrt(),
+            percentiles: PERCENTILES
+                .iter()
+                .map(|&p| Percentile {
+                    p,
+                    value: percentile(&sorted, f64::from(p)),
+                })
+                .collect(),
+            histogram: histogram(&sorted, buckets),
+            outliers,
+        })
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("stats always serialize")
+    }
+}
+
+/// The `p`th percentile of `sorted`, which must not be empty.
+fn percentile(sorted: &[u64], p: f64) -> f64 {
+    let rank = p / 100.0 * (sorted.len() - 1) as f64;
+    let (low, high) = (rank.floor() as usize, rank.ceil() as usize);
+    let (a, b) = (sorted[low] as f64, sorted[high] as f64);
+    a + (b - a) * (rank - low as f64)
+}
+
+/// Works in `u128` so the full range of `u64` totals can be split.
+fn histogram(sorted: &[u64], buckets: usize) -> Vec<Bucket> {
+    let (min, max) = (u128::from(sorted[0]), u128::from(sorted[sorted.len() - 1]));
+    let width = (max - min + 1).div_ceil(buckets.max(1) as u128);
+
+    let mut histogram = Vec::new();
+    let mut start = min;
+    let mut totals = sorted.iter().peekable();
+    while start <= max {
+        let end = (start + width - 1).min(max);
+        let mut elves = 0;
+        while totals.next_if(|&&t| u128::from(t) <= end).is_some() {
+            elves += 1;
+        }
+        histogram.push(Bucket {
+            start: start as u64,
+            end: end as u64,
+            elves,
+        });
+        start = end + 1;
+    }
+    histogram
+}
+
+impl fmt::Display for CalorieStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "elves    {:>12}", self.elves)?;
+        writeln!(f, "total    {:>12}", self.total)?;
+        writeln!(f, "min      {:>12}", self.min)?;
+        writeln!(f, "max      {:>12}", self.max)?;
+        writeln!(f, "mean     {:>12.1}", self.mean)?;
+        writeln!(f, "median   {:>12.1}", self.median)?;
+        writeln!(f, "std dev  {:>12.1}", self.std_dev)?;
+        for percentile in &self.percentiles {
+            let label = format!("p{}", percentile.p);
+            writeln!(f, "{:<8} {:>12.1}", label, percentile.value)?;
+        }
+
+        write!(f, "\nhistogram")?;
+        let tallest = self.histogram.iter().map(|b| b.elves).max().unwrap_or(0);
+        for bucket in &self.histogram {
+            let bar = bucket.elves * BAR_WIDTH / tallest.max(1);
+            write!(
+                f,
+                "\n{:>8} - {:<8} {:>5} {}",
+                bucket.start,
+                bucket.end,
+                bucket.elves,
+                "#".repeat(bar)
+            )?;
+        }
+
+        write!(f, "\n\noutliers")?;
+        if self.outliers.is_empty() {
+            write!(f, "\n  none")?;
+        }
+        for outlier in &self.outliers {
+            write!(f, "\n  elf {:<5} {:>8}", outlier.index, outlier.total)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day1::elves;
+
+    fn stats(input: &str, buckets: usize) -> CalorieStats {
+        let elves = elves(input).collect::<Result<Vec<Elf>, _>>().unwrap();
+        CalorieStats::new(&elves, buckets).unwrap()
+    }
+
+    #[test]
+    fn test_summary() {
+        // Totals 6000, 4000, 11000, 24000, 10000
+        let stats = stats(
+            "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000",
+            4,
+        );
+        assert_eq!(stats.elves, 5);
+        assert_eq!(stats.total, 55000);
+        assert_eq!((stats.min, stats.max), (4000, 24000));
+        assert_eq!(stats.mean, 11000.0);
+        assert_eq!(stats.median, 10000.0);
+        assert!((stats.std_dev - 48_800_000f64.sqrt()).abs() < 1e-9);
+        assert_eq!(
+            stats.percentiles[1],
+            Percentile {
+                p: 25,
+                value: 6000.0
+            }
+        );
+        assert_eq!(
+            stats.percentiles[4],
+            Percentile {
+                p: 90,
+                value: 18800.0
+            }
+        );
+
+        let counts = stats
+            .histogram
+            .iter()
+            .map(|b| (b.start, b.end, b.elves))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            counts,
+            vec![
+                (4000, 9000, 2),
+                (9001, 14001, 2),
+                (14002, 19002, 0),
+                (19003, 24000, 1)
+            ]
+        );
+        assert_eq!(
+            stats.outliers,
+            vec![Outlier {
+                index: 4,
+                total: 24000
+            }]
+        );
+    }
+
+    #[test]
+    fn test_single_elf() {
+        let stats = stats("500", 10);
+        assert_eq!(stats.median, 500.0);
+        assert_eq!(stats.std_dev, 0.0);
+        assert_eq!(
+            stats.histogram,
+            vec![Bucket {
+                start: 500,
+                end: 500,
+                elves: 1
+            }]
+        );
+        assert!(stats.outliers.is_empty());
+        assert_eq!(CalorieStats::new(&[], 10), None);
+    }
+
+    #[test]
+    fn test_full_range() {
+        let stats = stats(&format!("0\n\n{}\n\n{}", u64::MAX, u64::MAX), 4);
+        assert_eq!(stats.total, 2 * u128::from(u64::MAX));
+        assert_eq!(stats.histogram.len(), 4);
+        assert_eq!(stats.histogram[0].start, 0);
+        assert_eq!(stats.histogram[3].end, u64::MAX);
+        assert_eq!(stats.histogram[3].elves, 2);
+        assert!(stats.to_json().contains("\"total\": 36893488147419103230"));
+    }
+
+    #[test]
+    fn test_output() {
+        let stats = stats("100\n\n200\n\n300", 2);
+        let text = stats.to_string();
+        assert!(text.contains("median          200.0\n"));
+        assert!(text.contains("     100 - 200          2 ########################################"));
+        assert!(text.ends_with("outliers\n  none"));
+
+        let json: serde_json::Value = serde_json::from_str(&stats.to_json()).unwrap();
+        assert_eq!(json["elves"], 3);
+        assert_eq!(
+            json["percentiles"][0],
+            serde_json::json!({"p": 10, "value": 120.0})
+        );
+        assert_eq!(json["histogram"][1]["elves"], 1);
+    }
+}
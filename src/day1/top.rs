@@ -0,0 +1,99 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// Keeps the `k` largest items pushed so far in a min-heap, so memory stays
+/// bounded by `k` however many items stream past. The heap grows as items
+/// arrive, so a huge `k` costs nothing up front.
+#[derive(Debug, Clone)]
+pub struct TopK<T> {
+    k: usize,
+    heap: BinaryHeap<Reverse<T>>,
+}
+
+impl<T: Ord> TopK<T> {
+    pub fn new(k: usize) -> TopK<T> {
+        TopK {
+            k,
+            heap: BinaryHeap::new(),
+        }
+    }
+
+    pub fn push(&mut self, item: T) {
+        if self.heap.len() == self.k {
+            match self.heap.peek() {
+                Some(Reverse(smallest)) if item > *smallest => {
+                    self.heap.pop();
+                }
+                _ => return,
+            }
+        }
+        self.heap.push(Reverse(item));
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// The items kept, largest first. Fewer than `k` if fewer were pushed.
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(item)| item)
+            .collect()
+    }
+}
+
+impl<T: Ord> Extend<T> for TopK<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, items: I) {
+        for item in items {
+            self.push(item);
+        }
+    }
+}
+
+/// The `k` largest of `items`, largest first.
+pub fn top_k<T: Ord>(items: impl IntoIterator<Item = T>, k: usize) -> Vec<T> {
+    let mut top = TopK::new(k);
+    top.extend(items);
+    top.into_sorted_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_top_k() {
+        let items = [5, 1, 9, 3, 9, 7, 2];
+        assert_eq!(top_k(items, 3), vec![9, 9, 7]);
+        assert_eq!(top_k(items, 1), vec![9]);
+        assert_eq!(top_k(items, 0), Vec::<i32>::new());
+        assert_eq!(top_k(items, 10), vec![9, 9, 7, 5, 3, 2, 1]);
+        assert_eq!(top_k(Vec::<u32>::new(), 3), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn test_huge_k() {
+        assert_eq!(top_k([2, 7, 1], usize::MAX), vec![7, 2, 1]);
+        assert_eq!(top_k([2, 7, 1], 100_000_000_000), vec![7, 2, 1]);
+    }
+
+    #[test]
+    fn test_bounded() {
+        let mut top = TopK::new(4);
+        for n in 0..10_000u32 {
+            top.push(n.wrapping_mul(2_654_435_761) % 1000);
+            assert!(top.len() <= 4);
+        }
+        let mut all = (0..10_000u32)
+            .map(|n| n.wrapping_mul(2_654_435_761) % 1000)
+            .collect::<Vec<_>>();
+        all.sort_unstable_by(|a, b| b.cmp(a));
+        assert_eq!(top.into_sorted_vec(), all[..4]);
+    }
+}
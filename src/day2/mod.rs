@@ -0,0 +1,260 @@
+use crate::input;
+use crate::{ParseError, Solution};
+use std::ops::{Add, Sub};
+use tracing::debug;
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u8 = 2;
+
+    // The second column means a move in part 1 and an outcome in part 2,
+    // so both readings of the guide are parsed up front.
+    type Input = (Strategy, Strategy);
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let guide = (strategy_from_str(input)?, strategy_from_str_p2(input)?);
+        debug!(turns = guide.0.len(), "parsed strategy guide");
+        Ok(guide)
+    }
+
+    fn part1(input: &Self::Input) -> u32 {
+        play_strategy(&input.0)
+    }
+
+    fn part2(input: &Self::Input) -> u32 {
+        play_strategy(&input.1)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone)]
+pub enum Move {
+    Rock = 1,
+    Paper = 2,
+    Scissors = 3,
+}
+
+impl Move {
+    fn from_str(c: &str) -> Option<Move> {
+        match c {
+            "A" | "X" => Some(Move::Rock),
+            "B" | "Y" => Some(Move::Paper),
+            "C" | "Z" => Some(Move::Scissors),
+            _ => None,
+        }
+    }
+}
+
+impl Add<Outcome> for Move {
+    type Output = u32;
+
+    fn add(self, rhs: Outcome) -> u32 {
+        self as u32 + rhs as u32
+    }
+}
+enum Outcome {
+    Loss = 0,
+    Draw = 3,
+    Win = 6,
+}
+
+impl Outcome {
+    fn from_str(c: &str) -> Option<Outcome> {
+        match c {
+            "X" => Some(Outcome::Loss),
+            "Y" => Some(Outcome::Draw),
+            "Z" => Some(Outcome::Win),
+            _ => None,
+        }
+    }
+}
+
+impl Sub<Move> for Outcome {
+    type Output = Move;
+
+    fn sub(self, rhs: Move) -> Self::Output {
+        match (self, rhs) {
+            (Outcome::Loss, Move::Rock) => Move::Scissors,
+            (Outcome::Loss, Move::Paper) => Move::Rock,
+            (Outcome::Loss, Move::Scissors) => Move::Paper,
+            (Outcome::Win, Move::Rock) => Move::Paper,
+            (Outcome::Win, Move::Paper) => Move::Scissors,
+            (Outcome::Win, Move::Scissors) => Move::Rock,
+            (Outcome::Draw, _) => rhs,
+        }
+    }
+}
+/* X means you need to lose, Y means you need to end the round in a draw, and Z means you need to win. Good luck!" */
+
+pub type Turn = (Move, Move);
+pub type Strategy = Vec<Turn>;
+
+fn columns(line: &str) -> Result<(&str, &str), ParseError> {
+    let mut chs = line.split_whitespace();
+    let first = chs.next().unwrap_or(&line[line.len()..]);
+    let second = chs.next().ok_or_else(|| {
+        ParseError::new(Day2::DAY, line, &line[line.len()..], "expected two columns")
+    })?;
+    if let Some(extra) = chs.next() {
+        return Err(ParseError::new(
+            Day2::DAY,
+            line,
+            extra,
+            "unexpected third column",
+        ));
+    }
+    Ok((first, second))
+}
+
+fn parse_move(line: &str, c: &str) -> Result<Move, ParseError> {
+    Move::from_str(c).ok_or_else(|| {
+        ParseError::new(
+            Day2::DAY,
+            line,
+            c,
+            format!("'{}' doesn't map to a valid Move", c),
+        )
+    })
+}
+
+fn parse_outcome(line: &str, c: &str) -> Result<Outcome, ParseError> {
+    Outcome::from_str(c).ok_or_else(|| {
+        ParseError::new(
+            Day2::DAY,
+            line,
+            c,
+            format!("'{}' doesn't map to a valid Outcome", c),
+        )
+    })
+}
+
+fn strategy_from_str(input: &str) -> Result<Strategy, ParseError> {
+    let mut strategy: Strategy = vec![];
+    for (number, line) in input::lines(input) {
+        let turn =
+            columns(line).and_then(|(elf, me)| Ok((parse_move(line, elf)?, parse_move(line, me)?)));
+        strategy.push(turn.map_err(|e| e.at_line(number))?);
+    }
+    Ok(strategy)
+}
+
+fn strategy_from_str_p2(input: &str) -> Result<Strategy, ParseError> {
+    let mut strategy: Strategy = vec![];
+    for (number, line) in input::lines(input) {
+        let turn = columns(line).and_then(|(elf, outcome)| {
+            let elf_move = parse_move(line, elf)?;
+            let my_move = parse_outcome(line, outcome)? - elf_move;
+            Ok((elf_move, my_move))
+        });
+        strategy.push(turn.map_err(|e| e.at_line(number))?);
+    }
+    Ok(strategy)
+}
+
+fn play_strategy(strategy: &[Turn]) -> u32 {
+    let mut total_score = 0;
+
+    for turn in strategy {
+        total_score += get_score(*turn);
+    }
+
+    total_score
+}
+
+fn get_score(turn: Turn) -> u32 {
+    let (elf_move, my_move) = turn;
+
+    if elf_move == my_move {
+        return my_move + Outcome::Draw;
+    };
+
+    match (my_move, elf_move) {
+        (Move::Rock, Move::Paper)
+        | (Move::Paper, Move::Scissors)
+        | (Move::Scissors, Move::Rock) => my_move + Outcome::Loss,
+        (_, _) => my_move + Outcome::Win,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_turn() {
+        let elf_move = Move::from_str("A").unwrap();
+        let my_move = Move::from_str("Y").unwrap();
+        let turn = (elf_move, my_move);
+        assert_eq!(get_score(turn), 8);
+    }
+
+    #[test]
+    fn test_example_strategy() {
+        // Tests the example from the AoC explaination page
+        // https://adventofcode.com/2022/day/2
+
+        let strategy: Strategy = vec![
+            (Move::from_str("A").unwrap(), Move::from_str("Y").unwrap()),
+            (Move::from_str("B").unwrap(), Move::from_str("X").unwrap()),
+            (Move::from_str("C").unwrap(), Move::from_str("Z").unwrap()),
+        ];
+        let score = play_strategy(&strategy);
+        assert_eq!(score, 15);
+    }
+
+    #[test]
+    fn test_outcome_sub() {
+        let outcome = Outcome::Loss;
+        let elf_move = Move::Paper;
+
+        assert_eq!(outcome - elf_move, Move::Rock);
+
+        let outcome = Outcome::Draw;
+        assert_eq!(outcome - elf_move, Move::Paper);
+
+        let outcome = Outcome::Win;
+        assert_eq!(outcome - elf_move, Move::Scissors);
+    }
+
+    #[test]
+    fn test_part2_example() {
+        let strategy = strategy_from_str_p2("A Y\nB X\nC Z").unwrap();
+        let score = play_strategy(&strategy);
+        assert_eq!(score, 12)
+    }
+
+    #[test]
+    fn test_solution() {
+        let guide = Day2::parse("A Y\nB X\nC Z").unwrap();
+        assert_eq!(Day2::part1(&guide), 15);
+        assert_eq!(Day2::part2(&guide), 12);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = strategy_from_str("A Y\nB X\nD Z").unwrap_err();
+        assert_eq!((err.line, err.column), (3, 1));
+        assert_eq!(err.message, "'D' doesn't map to a valid Move");
+
+        let err = strategy_from_str_p2("A Y\nB  Q").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 4));
+        assert_eq!(err.message, "'Q' doesn't map to a valid Outcome");
+
+        let err = strategy_from_str("A").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 2));
+
+        let err = strategy_from_str("A Y Z").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 5));
+    }
+
+    #[test]
+    fn test_trailing_newline_and_crlf() {
+        let guide = Day2::parse("A Y\r\nB X\r\nC Z\r\n").unwrap();
+        assert_eq!(Day2::part1(&guide), 15);
+
+        let guide = Day2::parse("A Y\nB X\nC Z\n\n").unwrap();
+        assert_eq!(Day2::part2(&guide), 12);
+    }
+}
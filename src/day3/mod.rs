@@ -0,0 +1,329 @@
+use crate::input;
+use crate::{ParseError, Solution};
+use itertools::Itertools;
+use std::collections::hash_set::HashSet;
+use tracing::debug;
+
+static ASCII_LOWER: [char; 26] = [
+    'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's',
+    't', 'u', 'v', 'w', 'x', 'y', 'z',
+];
+static ASCII_UPPER: [char; 26] = [
+    'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S',
+    'T', 'U', 'V', 'W', 'X', 'Y', 'Z',
+];
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u8 = 3;
+
+    type Input = Vec<Rucksack>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Vec<Rucksack>, ParseError> {
+        let rucksacks = rucksacks_from_str(input)?;
+        debug!(rucksacks = rucksacks.len(), "parsed rucksacks");
+        Ok(rucksacks)
+    }
+
+    fn part1(rucksacks: &Vec<Rucksack>) -> u32 {
+        error_priority_sum(rucksacks)
+    }
+
+    fn part2(rucksacks: &Vec<Rucksack>) -> u32 {
+        let mut rucksacks = rucksacks.clone();
+        let mut error_sum = 0;
+        for (r1, r2, r3) in rucksacks.iter_mut().tuples() {
+            error_sum += find_badge_type(r1, r2, r3).priority();
+        }
+        error_sum
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Rucksack {
+    compartments: Vec<HashSet<char>>,
+    all_items: HashSet<char>,
+}
+
+trait CharExt {
+    fn priority(&self) -> u32;
+}
+
+impl CharExt for char {
+    fn priority(&self) -> u32 {
+        if self.is_uppercase() {
+            (ASCII_UPPER.iter().position(|c| c == self).unwrap() + 26 + 1) as u32
+        } else {
+            (ASCII_LOWER.iter().position(|c| c == self).unwrap() + 1) as u32
+        }
+    }
+}
+
+impl Rucksack {
+    fn from_str(input_str: &str) -> Rucksack {
+        let mut chars = input_str.chars();
+
+        let mut container1 = HashSet::new();
+        let mut container2 = HashSet::new();
+
+        let mut all_items = HashSet::new();
+        while let (Some(c1), Some(c2)) = (chars.next(), chars.next_back()) {
+            container1.insert(c1);
+            container2.insert(c2);
+
+            all_items.insert(c1);
+            all_items.insert(c2);
+        }
+
+        Rucksack {
+            compartments: vec![container1, container2],
+            all_items,
+        }
+    }
+
+    fn get_error(&self) -> char {
+        *self.compartments[0]
+            .intersection(&self.compartments[1])
+            .last()
+            .expect("rucksacks_from_str checks for an item in both compartments")
+    }
+}
+
+fn error_priority_sum(rucksacks: &[Rucksack]) -> u32 {
+    let mut priority_sum = 0;
+    for rucksack in rucksacks {
+        let common_item = rucksack.get_error();
+        priority_sum += common_item.priority();
+    }
+
+    priority_sum
+}
+
+fn find_badge_type(rucksack1: &mut Rucksack, rucksack2: &Rucksack, rucksack3: &Rucksack) -> char {
+    rucksack1
+        .all_items
+        .retain(|item| rucksack2.all_items.contains(item));
+    rucksack1
+        .all_items
+        .retain(|item| rucksack3.all_items.contains(item));
+
+    *rucksack1
+        .all_items
+        .iter()
+        .last()
+        .expect("rucksacks_from_str checks every group has a badge")
+}
+
+fn validate_rucksack(line: &str) -> Result<(), ParseError> {
+    if let Some((i, c)) = line.char_indices().find(|(_, c)| !c.is_ascii_alphabetic()) {
+        return Err(ParseError::new(
+            Day3::DAY,
+            line,
+            &line[i..i + c.len_utf8()],
+            format!("'{}' is not a valid item", c),
+        ));
+    }
+    if !line.len().is_multiple_of(2) {
+        return Err(ParseError::new(
+            Day3::DAY,
+            line,
+            line,
+            "rucksack can't be split into two equal compartments",
+        ));
+    }
+    Ok(())
+}
+
+fn has_badge(group: &[Rucksack]) -> bool {
+    group[0]
+        .all_items
+        .iter()
+        .any(|item| group[1..].iter().all(|r| r.all_items.contains(item)))
+}
+
+/// Also checks that every rucksack has an item in both compartments, and
+/// every group of three an item they all carry, so neither part can come up
+/// empty.
+fn rucksacks_from_str(input: &str) -> Result<Vec<Rucksack>, ParseError> {
+    let mut rucksacks = vec![];
+    for (number, line) in input::lines(input) {
+        validate_rucksack(line).map_err(|e| e.at_line(number))?;
+        let rucksack = Rucksack::from_str(line);
+        if rucksack.compartments[0].is_disjoint(&rucksack.compartments[1]) {
+            let message = "no item is in both compartments";
+            return Err(ParseError::new(Day3::DAY, line, line, message).at_line(number));
+        }
+        rucksacks.push(rucksack);
+
+        let group = &rucksacks[(rucksacks.len() - 1) / 3 * 3..];
+        if group.len() == 3 && !has_badge(group) {
+            let message = "no item is carried by all three elves in this group";
+            return Err(ParseError::new(Day3::DAY, line, line, message).at_line(number));
+        }
+    }
+
+    Ok(rucksacks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rucksack_from_str() {
+        let input_str = "vJrwpWtwJgWrhcsFMMfFFhFp";
+        let rucksack = Rucksack::from_str(input_str);
+
+        println!("{:?}", rucksack);
+    }
+
+    #[test]
+    fn test_find_common_entry() {
+        let input_str = "vJrwpWtwJgWrhcsFMMfFFhFp";
+        let rucksack = Rucksack::from_str(input_str);
+
+        let common_item = rucksack.compartments[0]
+            .intersection(&rucksack.compartments[1])
+            .last();
+        assert_eq!(*common_item.unwrap(), 'p')
+    }
+
+    #[test]
+    fn test_aoc_examples() {
+        let input_str = "vJrwpWtwJgWrhcsFMMfFFhFp";
+        let rucksack = Rucksack::from_str(input_str);
+
+        let common_item = rucksack.get_error();
+        assert_eq!(common_item, 'p');
+
+        let input_str = "jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL";
+        let rucksack = Rucksack::from_str(input_str);
+
+        let common_item = rucksack.get_error();
+        assert_eq!(common_item, 'L');
+
+        let input_str = "PmmdzqPrVvPwwTWBwg";
+        let rucksack = Rucksack::from_str(input_str);
+
+        let common_item = rucksack.get_error();
+        assert_eq!(common_item, 'P');
+
+        let input_str = "wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn";
+        let rucksack = Rucksack::from_str(input_str);
+
+        let common_item = rucksack.get_error();
+        assert_eq!(common_item, 'v');
+
+        let input_str = "ttgJtRGJQctTZtZT";
+        let rucksack = Rucksack::from_str(input_str);
+
+        let common_item = rucksack.get_error();
+        assert_eq!(common_item, 't');
+
+        let input_str = "CrZsJsPPZsGzwwsLwLmpwMDw";
+        let rucksack = Rucksack::from_str(input_str);
+
+        let common_item = rucksack.get_error();
+        assert_eq!(common_item, 's');
+    }
+
+    #[test]
+    fn test_char_priority() {
+        assert_eq!('p'.priority(), 16);
+        assert_eq!('L'.priority(), 38);
+        assert_eq!('P'.priority(), 42);
+        assert_eq!('v'.priority(), 22);
+        assert_eq!('t'.priority(), 20);
+        assert_eq!('s'.priority(), 19);
+    }
+
+    #[test]
+    fn test_error_sum() {
+        let rucksacks = vec![
+            Rucksack::from_str("vJrwpWtwJgWrhcsFMMfFFhFp"),
+            Rucksack::from_str("jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL"),
+            Rucksack::from_str("PmmdzqPrVvPwwTWBwg"),
+            Rucksack::from_str("wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn"),
+            Rucksack::from_str("ttgJtRGJQctTZtZT"),
+            Rucksack::from_str("CrZsJsPPZsGzwwsLwLmpwMDw"),
+        ];
+        let sum_of_priorities = error_priority_sum(&rucksacks);
+        assert_eq!(sum_of_priorities, 157);
+    }
+
+    #[test]
+    fn test_find_badge_type() {
+        let mut rucksack1 = Rucksack::from_str("vJrwpWtwJgWrhcsFMMfFFhFp");
+        let rucksack2 = Rucksack::from_str("jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL");
+        let rucksack3 = Rucksack::from_str("PmmdzqPrVvPwwTWBwg");
+
+        assert_eq!(find_badge_type(&mut rucksack1, &rucksack2, &rucksack3), 'r');
+
+        let mut rucksack4 = Rucksack::from_str("wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn");
+        let rucksack5 = Rucksack::from_str("ttgJtRGJQctTZtZT");
+        let rucksack6 = Rucksack::from_str("CrZsJsPPZsGzwwsLwLmpwMDw");
+        assert_eq!(find_badge_type(&mut rucksack4, &rucksack5, &rucksack6), 'Z');
+    }
+
+    #[test]
+    fn test_error_sum_day2() {
+        let mut rucksack1 = Rucksack::from_str("vJrwpWtwJgWrhcsFMMfFFhFp");
+        let rucksack2 = Rucksack::from_str("jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL");
+        let rucksack3 = Rucksack::from_str("PmmdzqPrVvPwwTWBwg");
+
+        let mut error_sum = find_badge_type(&mut rucksack1, &rucksack2, &rucksack3).priority();
+
+        let mut rucksack4 = Rucksack::from_str("wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn");
+        let rucksack5 = Rucksack::from_str("ttgJtRGJQctTZtZT");
+        let rucksack6 = Rucksack::from_str("CrZsJsPPZsGzwwsLwLmpwMDw");
+        error_sum += find_badge_type(&mut rucksack4, &rucksack5, &rucksack6).priority();
+
+        assert_eq!(error_sum, 70);
+    }
+
+    #[test]
+    fn test_solution() {
+        let rucksacks = Day3::parse(
+            "vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw",
+        )
+        .unwrap();
+        assert_eq!(Day3::part1(&rucksacks), 157);
+        assert_eq!(Day3::part2(&rucksacks), 70);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = rucksacks_from_str("vJrwpWtwJgWrhcsFMMfFFhFp\nabc1").unwrap_err();
+        assert_eq!((err.line, err.column, err.width), (2, 4, 1));
+
+        let err = rucksacks_from_str("abc").unwrap_err();
+        assert_eq!((err.line, err.column, err.width), (1, 1, 3));
+
+        let err = rucksacks_from_str("vJrwpWtwJgWrhcsFMMfFFhFp\nabcd").unwrap_err();
+        assert_eq!((err.line, err.column, err.width), (2, 1, 4));
+        assert_eq!(err.message, "no item is in both compartments");
+
+        let err = rucksacks_from_str("aa\nbb\naa\ncc").unwrap_err();
+        assert_eq!((err.line, err.column, err.width), (3, 1, 2));
+        assert_eq!(
+            err.message,
+            "no item is carried by all three elves in this group"
+        );
+    }
+
+    #[test]
+    fn test_trailing_newline_and_crlf() {
+        let rucksacks =
+            rucksacks_from_str("vJrwpWtwJgWrhcsFMMfFFhFp\r\nPmmdzqPrVvPwwTWBwg\r\n").unwrap();
+        assert_eq!(rucksacks.len(), 2);
+        assert_eq!(rucksacks[1].get_error(), 'P');
+    }
+}
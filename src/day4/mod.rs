@@ -0,0 +1,174 @@
+use crate::input;
+use crate::{ParseError, Solution};
+use std::collections::hash_set::HashSet;
+use tracing::debug;
+
+pub type SectionPair = (HashSet<u32>, HashSet<u32>);
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u8 = 4;
+
+    type Input = Vec<SectionPair>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Vec<SectionPair>, ParseError> {
+        let sections = sections_from_str(input)?;
+        debug!(pairs = sections.len(), "parsed section pairs");
+        Ok(sections)
+    }
+
+    fn part1(sections: &Vec<SectionPair>) -> usize {
+        sections.iter().filter(|s| complete_overlap(s)).count()
+    }
+
+    fn part2(sections: &Vec<SectionPair>) -> usize {
+        sections.iter().filter(|s| any_overlap(s)).count()
+    }
+}
+
+fn section_range(line: &str, seg: &str) -> Result<(u32, u32), ParseError> {
+    let (start, end) = seg.split_once('-').ok_or_else(|| {
+        ParseError::new(
+            Day4::DAY,
+            line,
+            seg,
+            format!("'{}' is not a section range", seg),
+        )
+    })?;
+    let bound = |s: &str| {
+        s.parse::<u32>().map_err(|_| {
+            ParseError::new(
+                Day4::DAY,
+                line,
+                s,
+                format!("'{}' is not a section number", s),
+            )
+        })
+    };
+
+    let range = (bound(start)?, bound(end)?);
+    if range.0 > range.1 {
+        return Err(ParseError::new(
+            Day4::DAY,
+            line,
+            seg,
+            "range ends before it starts",
+        ));
+    }
+    Ok(range)
+}
+
+fn section_pairs_from_str(input_str: &str) -> Result<SectionPair, ParseError> {
+    // Input string looks like 2-4,6-8
+    // Want to turn into 2..=4  6..=8
+    let (elf1, elf2) = input_str.split_once(',').ok_or_else(|| {
+        ParseError::new(
+            Day4::DAY,
+            input_str,
+            input_str,
+            "expected two comma separated ranges",
+        )
+    })?;
+
+    let (elf1_range, elf2_range) = (
+        section_range(input_str, elf1)?,
+        section_range(input_str, elf2)?,
+    );
+    Ok((
+        HashSet::from_iter(elf1_range.0..=elf1_range.1),
+        HashSet::from_iter(elf2_range.0..=elf2_range.1),
+    ))
+}
+
+fn sections_from_str(input_str: &str) -> Result<Vec<SectionPair>, ParseError> {
+    let mut sections = Vec::new();
+    for (number, in_string) in input::lines(input_str) {
+        sections.push(section_pairs_from_str(in_string).map_err(|e| e.at_line(number))?);
+    }
+
+    Ok(sections)
+}
+
+fn complete_overlap(sections: &SectionPair) -> bool {
+    sections.0.is_subset(&sections.1) || sections.1.is_subset(&sections.0)
+}
+
+fn any_overlap(sections: &SectionPair) -> bool {
+    !sections.0.is_disjoint(&sections.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        assert_eq!(1, 1);
+    }
+
+    #[test]
+    fn test_segments_from_str() {
+        let input_str = "2-4,6-8";
+        let sections = section_pairs_from_str(input_str).unwrap();
+
+        assert_eq!(
+            sections,
+            (
+                HashSet::from_iter(vec![2, 3, 4]),
+                HashSet::from_iter(vec![6, 7, 8])
+            )
+        );
+    }
+
+    #[test]
+    fn test_sections_from_str() {
+        // Example from AoC problem page
+        // https://adventofcode.com/2022/day/4
+        let test_str = "2-4,6-8
+2-3,4-5
+5-7,7-9
+2-8,3-7
+6-6,4-6
+2-6,4-8";
+        let sections = sections_from_str(test_str).unwrap();
+        println!("{:?}", sections);
+
+        assert_eq!(Day4::part1(&sections), 2);
+        assert_eq!(Day4::part2(&sections), 4);
+    }
+
+    #[test]
+    fn test_complete_overlap() {
+        let sections = section_pairs_from_str("2-8,3-7").unwrap();
+        assert!(complete_overlap(&sections));
+
+        let sections = section_pairs_from_str("5-7,7-9").unwrap();
+        assert!(!complete_overlap(&sections))
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = sections_from_str("2-4,6-8\n2-x,4-5").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+        assert_eq!(err.message, "'x' is not a section number");
+
+        let err = section_pairs_from_str("2-4").unwrap_err();
+        assert_eq!(err.message, "expected two comma separated ranges");
+
+        let err = section_pairs_from_str("2-4,68").unwrap_err();
+        assert_eq!((err.column, err.width), (5, 2));
+
+        let err = section_pairs_from_str("4-2,6-8").unwrap_err();
+        assert_eq!(err.message, "range ends before it starts");
+    }
+
+    #[test]
+    fn test_trailing_newline_and_crlf() {
+        let sections = sections_from_str("2-4,6-8\r\n2-8,3-7 \r\n").unwrap();
+        assert_eq!(sections.len(), 2);
+        assert_eq!(Day4::part1(&sections), 1);
+    }
+}
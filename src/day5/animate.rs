@@ -0,0 +1,144 @@
+use super::drawing::render_lines;
+use super::{Instruction, Stack};
+use std::time::Duration;
+
+const HIGHLIGHT: &str = "\x1b[1;33m";
+const RESET: &str = "\x1b[0m";
+
+const MIN_DELAY: Duration = Duration::from_millis(10);
+const MAX_DELAY: Duration = Duration::from_secs(5);
+
+/// The crates an instruction just landed on top of a stack.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Highlight {
+    /// 0-based index of the destination stack
+    pub stack: usize,
+    pub count: usize,
+}
+
+impl Highlight {
+    /// The crates to highlight after `instruction`, if it leaves a chunk on
+    /// top of a single stack.
+    pub fn after(instruction: &Instruction) -> Option<Highlight> {
+        match *instruction {
+            Instruction::Move(ref m) => Some(Highlight {
+                stack: m.to - 1,
+                count: m.count,
+            }),
+            Instruction::Reverse { count, stack } => Some(Highlight {
+                stack: stack - 1,
+                count,
+            }),
+            Instruction::Swap { .. } | Instruction::Repeat { .. } => None,
+        }
+    }
+}
+
+/// Renders `stacks` like `render_drawing`, with the highlighted crates wrapped
+/// in ANSI colour codes. Lines are returned separately so callers can pick the
+/// line ending their terminal mode needs.
+pub fn render_highlighted(stacks: &[Stack], highlight: Option<Highlight>) -> Vec<String> {
+    render_lines(stacks, |stack, row, boxed| match highlight {
+        Some(h) if h.stack == stack && row + h.count >= stacks[stack].len() => {
+            format!("{}{}{}", HIGHLIGHT, boxed, RESET)
+        }
+        _ => boxed,
+    })
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Control {
+    Continue,
+    Step,
+    Quit,
+}
+
+/// Playback state for the animation, driven by key presses.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Player {
+    pub delay: Duration,
+    pub paused: bool,
+}
+
+impl Player {
+    pub fn new(delay: Duration) -> Player {
+        Player {
+            delay: delay.clamp(MIN_DELAY, MAX_DELAY),
+            paused: false,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: char) -> Control {
+        match key {
+            ' ' | 'p' => self.paused = !self.paused,
+            'n' | 's' => {
+                self.paused = true;
+                return Control::Step;
+            }
+            '+' | '=' => self.delay = (self.delay / 2).max(MIN_DELAY),
+            '-' => self.delay = (self.delay * 2).min(MAX_DELAY),
+            'q' => return Control::Quit,
+            _ => {}
+        }
+        Control::Continue
+    }
+
+    pub fn help(&self) -> String {
+        format!(
+            "space: {}  n: step  +/-: speed ({}ms)  q: quit",
+            if self.paused { "resume" } else { "pause" },
+            self.delay.as_millis()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_highlighted() {
+        let stacks = crate::day5::stacks_of(&["ZN", "MCD", "P"]);
+
+        let plain = render_highlighted(&stacks, None);
+        assert_eq!(plain.join("\n"), crate::day5::render_drawing(&stacks));
+
+        let lines = render_highlighted(&stacks, Some(Highlight { stack: 1, count: 2 }));
+        assert_eq!(lines[0], "    \x1b[1;33m[D]\x1b[0m    ");
+        assert_eq!(lines[1], "[N] \x1b[1;33m[C]\x1b[0m    ");
+        assert_eq!(lines[2], "[Z] [M] [P]");
+    }
+
+    #[test]
+    fn test_player_keys() {
+        let mut player = Player::new(Duration::from_millis(200));
+
+        assert_eq!(player.handle_key(' '), Control::Continue);
+        assert!(player.paused);
+        assert_eq!(player.handle_key(' '), Control::Continue);
+        assert!(!player.paused);
+
+        assert_eq!(player.handle_key('n'), Control::Step);
+        assert!(player.paused);
+
+        player.handle_key('+');
+        assert_eq!(player.delay, Duration::from_millis(100));
+        player.handle_key('-');
+        player.handle_key('-');
+        assert_eq!(player.delay, Duration::from_millis(400));
+
+        assert_eq!(player.handle_key('q'), Control::Quit);
+    }
+
+    #[test]
+    fn test_player_delay_bounds() {
+        let mut player = Player::new(Duration::ZERO);
+        assert_eq!(player.delay, MIN_DELAY);
+        player.handle_key('+');
+        assert_eq!(player.delay, MIN_DELAY);
+
+        let mut player = Player::new(Duration::from_secs(60));
+        player.handle_key('-');
+        assert_eq!(player.delay, MAX_DELAY);
+    }
+}
@@ -0,0 +1,118 @@
+use super::{Move, Stack};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The crane model used to carry out each instruction.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum Crane {
+    /// Moves crates one at a time, so a chunk lands in reverse order
+    CrateMover9000,
+    /// Moves a whole chunk at once, keeping its order
+    CrateMover9001,
+}
+
+impl Crane {
+    pub fn from_model(model: &str) -> Option<Crane> {
+        match model {
+            "9000" => Some(Crane::CrateMover9000),
+            "9001" => Some(Crane::CrateMover9001),
+            _ => None,
+        }
+    }
+
+    /// Carries out a validated move. The moved chunk is drained straight
+    /// from the top of one stack onto the other, so this takes time
+    /// proportional to the number of crates moved and doesn't allocate beyond
+    /// growing the destination.
+    pub fn move_crates(self, stacks: &mut [Stack], instruction: &Move) {
+        // Whether lifted one at a time or all at once, crates put straight back
+        // on the stack they came from end up where they started
+        if instruction.from == instruction.to {
+            return;
+        }
+
+        let (from, to) = pair_mut(stacks, instruction.from - 1, instruction.to - 1);
+        let moved = from.drain(from.len() - instruction.count..);
+        match self {
+            Crane::CrateMover9000 => to.extend(moved.rev()),
+            Crane::CrateMover9001 => to.extend(moved),
+        }
+    }
+}
+
+/// Borrows two different stacks mutably at once.
+fn pair_mut(stacks: &mut [Stack], a: usize, b: usize) -> (&mut Stack, &mut Stack) {
+    if a < b {
+        let (low, high) = stacks.split_at_mut(b);
+        (&mut low[a], &mut high[0])
+    } else {
+        let (low, high) = stacks.split_at_mut(a);
+        (&mut high[0], &mut low[b])
+    }
+}
+
+impl fmt::Display for Crane {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Crane::CrateMover9000 => write!(f, "CrateMover 9000"),
+            Crane::CrateMover9001 => write!(f, "CrateMover 9001"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day5::stacks_of;
+
+    #[test]
+    fn test_move_crates() {
+        let instruction = Move {
+            count: 3,
+            from: 1,
+            to: 2,
+        };
+
+        let mut stacks = stacks_of(&["ZND", "P"]);
+        Crane::CrateMover9000.move_crates(&mut stacks, &instruction);
+        assert_eq!(stacks, stacks_of(&["", "PDNZ"]));
+
+        let mut stacks = stacks_of(&["ZND", "P"]);
+        Crane::CrateMover9001.move_crates(&mut stacks, &instruction);
+        assert_eq!(stacks, stacks_of(&["", "PZND"]));
+    }
+
+    #[test]
+    fn test_move_onto_same_stack() {
+        let instruction = Move {
+            count: 2,
+            from: 1,
+            to: 1,
+        };
+        for crane in [Crane::CrateMover9000, Crane::CrateMover9001] {
+            let mut stacks = stacks_of(&["ZND", "P"]);
+            crane.move_crates(&mut stacks, &instruction);
+            assert_eq!(stacks, stacks_of(&["ZND", "P"]));
+        }
+    }
+
+    #[test]
+    fn test_matches_one_at_a_time() {
+        // The crane as the puzzle describes it, one crate per trip
+        fn one_at_a_time(stacks: &mut [Stack], instruction: &Move) {
+            for _ in 0..instruction.count {
+                let c = stacks[instruction.from - 1].pop().unwrap();
+                stacks[instruction.to - 1].push(c);
+            }
+        }
+
+        let mut expected = stacks_of(&["ABCDEFGH", "IJKL", ""]);
+        let mut actual = expected.clone();
+        for (count, from, to) in [(3, 1, 3), (5, 1, 2), (9, 2, 3), (12, 3, 1), (4, 1, 2)] {
+            let instruction = Move { count, from, to };
+            one_at_a_time(&mut expected, &instruction);
+            Crane::CrateMover9000.move_crates(&mut actual, &instruction);
+            assert_eq!(actual, expected);
+        }
+    }
+}
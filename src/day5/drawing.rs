@@ -0,0 +1,330 @@
+use super::{Crate, Day5, Stack};
+use crate::input;
+use crate::{ParseError, Solution};
+use std::iter;
+use std::ops::Range;
+
+/// Conversions between a single stack and its one-column drawing.
+pub trait StackExt {
+    fn from_str(input_str: &str) -> Result<Stack, ParseError>;
+    fn render(&self) -> String;
+}
+
+impl StackExt for Stack {
+    fn from_str(input_str: &str) -> Result<Stack, ParseError> {
+        let lines = input::lines(input_str).collect::<Vec<_>>();
+        let mut stacks = parse_drawing_lines(&lines)?;
+        if stacks.len() != 1 {
+            let &(number, idx_line) = lines.last().unwrap();
+            return Err(ParseError::new(
+                Day5::DAY,
+                idx_line,
+                idx_line.trim(),
+                format!("expected a single stack, found {}", stacks.len()),
+            )
+            .at_line(number));
+        }
+        Ok(stacks.remove(0))
+    }
+
+    fn render(&self) -> String {
+        render_drawing(std::slice::from_ref(self))
+    }
+}
+
+/// Parses a stack drawing, the crates in each column listed bottom to top.
+pub fn parse_drawing(input_str: &str) -> Result<Vec<Stack>, ParseError> {
+    parse_drawing_lines(&input::lines(input_str).collect::<Vec<_>>())
+}
+
+pub(super) fn parse_drawing_lines(lines: &[(usize, &str)]) -> Result<Vec<Stack>, ParseError> {
+    /*
+    Expected input string ex
+
+        [D]
+    [N] [C]
+    [Z] [M] [P]
+     1   2   3
+
+    Crates belong to the stack whose number they sit over, so labels can be
+    wider than one character and there can be more than nine stacks.
+    */
+
+    let Some((&(idx_line_no, idx_line), stack_lines)) = lines.split_last() else {
+        return Err(ParseError::new(
+            Day5::DAY,
+            "",
+            "",
+            "expected a stack drawing",
+        ));
+    };
+
+    let stack_ids = stack_numbers(idx_line_no, idx_line)?;
+    let mut out: Vec<Stack> = vec![Vec::new(); stack_ids.len()];
+
+    for &(line_no, stack_line) in stack_lines.iter().rev() {
+        for token in tokens(stack_line) {
+            let crate_ = parse_crate(line_no, stack_line, &token)?;
+            let mut below = stack_ids
+                .iter()
+                .enumerate()
+                .filter(|(_, id)| overlaps(&id.columns, &token.columns))
+                .map(|(i, _)| i);
+            match (below.next(), below.next()) {
+                (Some(i), None) => out[i].push(crate_),
+                (None, _) => {
+                    return Err(ParseError::new(
+                        Day5::DAY,
+                        stack_line,
+                        token.text,
+                        format!("{} isn't above any stack number", token.text),
+                    )
+                    .at_line(line_no))
+                }
+                (Some(_), Some(_)) => {
+                    return Err(ParseError::new(
+                        Day5::DAY,
+                        stack_line,
+                        token.text,
+                        format!("{} is above more than one stack number", token.text),
+                    )
+                    .at_line(line_no))
+                }
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// A run of non-space characters on a drawing line, with the character
+/// columns it covers.
+struct Token<'a> {
+    columns: Range<usize>,
+    text: &'a str,
+}
+
+fn tokens(line: &str) -> Vec<Token<'_>> {
+    let mut out = Vec::new();
+    let mut start = None;
+    let ends = line.char_indices().chain(iter::once((line.len(), ' ')));
+    for (column, (offset, c)) in ends.enumerate() {
+        match (c == ' ', start) {
+            (false, None) => start = Some((column, offset)),
+            (true, Some((start_column, start_offset))) => {
+                out.push(Token {
+                    columns: start_column..column,
+                    text: &line[start_offset..offset],
+                });
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+fn overlaps(a: &Range<usize>, b: &Range<usize>) -> bool {
+    a.start < b.end && b.start < a.end
+}
+
+/// Reads the line of stack numbers, which must count up from 1.
+fn stack_numbers(line_no: usize, line: &str) -> Result<Vec<Token<'_>>, ParseError> {
+    let ids = tokens(line);
+    if ids.is_empty() {
+        return Err(ParseError::new(
+            Day5::DAY,
+            line,
+            line,
+            "expected a line of stack numbers below the drawing",
+        )
+        .at_line(line_no));
+    }
+
+    for (i, id) in ids.iter().enumerate() {
+        let message = match id.text.parse::<usize>() {
+            Ok(n) if n == i + 1 => continue,
+            Ok(n) => format!("expected stack {} here, found {}", i + 1, n),
+            Err(_) => format!("'{}' is not a stack number", id.text),
+        };
+        return Err(ParseError::new(Day5::DAY, line, id.text, message).at_line(line_no));
+    }
+    Ok(ids)
+}
+
+fn parse_crate(line_no: usize, line: &str, token: &Token) -> Result<Crate, ParseError> {
+    let error = |text: &str, message: String| {
+        ParseError::new(Day5::DAY, line, text, message).at_line(line_no)
+    };
+
+    let label = token
+        .text
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+        .ok_or_else(|| {
+            error(
+                token.text,
+                format!("expected a crate like '[A]', found '{}'", token.text),
+            )
+        })?;
+
+    Crate::new(label).ok_or_else(|| {
+        match label.char_indices().find(|&(_, c)| !c.is_alphanumeric()) {
+            Some((i, c)) => error(
+                &label[i..i + c.len_utf8()],
+                format!("'{}' is not a valid crate label", label),
+            ),
+            None => error(token.text, "crate label is empty".to_owned()),
+        }
+    })
+}
+
+/// Renders `stacks` in the puzzle's drawing format, every line padded to the
+/// full width and followed by the line of stack numbers.
+pub fn render_drawing(stacks: &[Stack]) -> String {
+    render_lines(stacks, |_, _, boxed| boxed).join("\n")
+}
+
+/// Builds the drawing line by line. Each column is as wide as its widest crate
+/// or its stack number, with narrower entries centred in it.
+/// `render_crate(stack, row, boxed)` is given each crate's bracketed label so
+/// callers can decorate individual crates.
+pub(super) fn render_lines(
+    stacks: &[Stack],
+    render_crate: impl Fn(usize, usize, String) -> String,
+) -> Vec<String> {
+    let height = stacks.iter().map(Vec::len).max().unwrap_or(0);
+    let widths = stacks
+        .iter()
+        .enumerate()
+        .map(|(i, stack)| {
+            let widest = stack.iter().map(|c| c.width() + 2).max().unwrap_or(0);
+            widest.max((i + 1).to_string().len()).max(3)
+        })
+        .collect::<Vec<usize>>();
+
+    let centre = |text: String, visible: usize, width: usize| {
+        let left = (width - visible) / 2;
+        format!(
+            "{}{}{}",
+            " ".repeat(left),
+            text,
+            " ".repeat(width - visible - left)
+        )
+    };
+
+    let mut lines = Vec::new();
+    for row in (0..height).rev() {
+        let line = stacks
+            .iter()
+            .enumerate()
+            .map(|(i, stack)| match stack.get(row) {
+                Some(c) => {
+                    let boxed = format!("[{}]", c);
+                    centre(render_crate(i, row, boxed), c.width() + 2, widths[i])
+                }
+                None => " ".repeat(widths[i]),
+            })
+            .collect::<Vec<String>>();
+        lines.push(line.join(" "));
+    }
+
+    let idx_line = (1..=stacks.len())
+        .map(|i| {
+            let id = i.to_string();
+            let visible = id.len();
+            centre(id, visible, widths[i - 1])
+        })
+        .collect::<Vec<String>>();
+    lines.push(idx_line.join(" "));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day5::stacks_of;
+
+    const EXAMPLE: &str = "    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 ";
+
+    #[test]
+    fn test_render_drawing() {
+        let stacks = stacks_of(&["ZN", "MCD", "P"]);
+        assert_eq!(render_drawing(&stacks), EXAMPLE);
+    }
+
+    #[test]
+    fn test_drawing_round_trip() {
+        let stacks = parse_drawing(EXAMPLE).unwrap();
+        assert_eq!(parse_drawing(&render_drawing(&stacks)).unwrap(), stacks);
+
+        let stacks = stacks_of(&["", "A", "", "BCDE"]);
+        assert_eq!(parse_drawing(&render_drawing(&stacks)).unwrap(), stacks);
+
+        let stacks = stacks_of(&["", ""]);
+        assert_eq!(parse_drawing(&render_drawing(&stacks)).unwrap(), stacks);
+    }
+
+    #[test]
+    fn test_wide_labels() {
+        let drawing = "         [ABC]\n[AB]      [D]\n[Z]  [M]  [P]\n 1    2    3";
+        let stacks = parse_drawing(drawing).unwrap();
+        let labels = stacks
+            .iter()
+            .map(|stack| stack.iter().map(Crate::as_str).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            labels,
+            vec![vec!["Z", "AB"], vec!["M"], vec!["P", "D", "ABC"]]
+        );
+
+        let rendered = render_drawing(&stacks);
+        assert_eq!(
+            rendered,
+            "         [ABC]\n[AB]      [D] \n[Z]  [M]  [P] \n 1    2    3  "
+        );
+        assert_eq!(parse_drawing(&rendered).unwrap(), stacks);
+    }
+
+    #[test]
+    fn test_more_than_nine_stacks() {
+        let drawing = "[A] [B] [C] [D] [E] [F] [G] [H] [I] [J] [K]
+ 1   2   3   4   5   6   7   8   9  10  11 ";
+        let stacks = parse_drawing(drawing).unwrap();
+        assert_eq!(stacks.len(), 11);
+        assert_eq!(stacks[9], stacks_of(&["J"])[0]);
+        assert_eq!(stacks[10], stacks_of(&["K"])[0]);
+        assert_eq!(parse_drawing(&render_drawing(&stacks)).unwrap(), stacks);
+    }
+
+    #[test]
+    fn test_drawing_errors() {
+        let err = parse_drawing("[A] [B]\n 1   3 ").unwrap_err();
+        assert_eq!(err.message, "expected stack 2 here, found 3");
+        assert_eq!((err.line, err.column), (2, 6));
+
+        let err = parse_drawing("[A]     [B]\n 1   2 ").unwrap_err();
+        assert_eq!(err.message, "[B] isn't above any stack number");
+
+        let err = parse_drawing("[A] [B#C]\n 1   2 ").unwrap_err();
+        assert_eq!(err.message, "'B#C' is not a valid crate label");
+        assert_eq!(err.column, 7);
+
+        let err = parse_drawing("[A] B\n 1   2 ").unwrap_err();
+        assert_eq!(err.message, "expected a crate like '[A]', found 'B'");
+    }
+
+    #[test]
+    fn test_stack_from_str() {
+        let stack = Stack::from_str("[D]\n[C]\n[M]\n 1 ").unwrap();
+        assert_eq!(stack, stacks_of(&["MCD"])[0]);
+        assert_eq!(stack.render(), "[D]\n[C]\n[M]\n 1 ");
+        assert_eq!(Stack::from_str(&stack.render()).unwrap(), stack);
+
+        let err = Stack::from_str(EXAMPLE).unwrap_err();
+        assert_eq!(err.message, "expected a single stack, found 3");
+        assert_eq!(err.line, 4);
+    }
+}
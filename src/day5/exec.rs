@@ -0,0 +1,364 @@
+use super::{Crane, Instruction, Move, Problem, Stack};
+use std::convert::Infallible;
+use std::error::Error;
+use std::fmt;
+use tracing::{debug, trace};
+
+/// Why an instruction can't be carried out against the current stacks.
+/// `index` is the instruction's position in the list, counting from 0, and
+/// `instruction` is the statement that failed, which may be inside a `repeat`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ExecError {
+    NoSuchStack {
+        index: usize,
+        instruction: Instruction,
+        stack: usize,
+        stacks: usize,
+    },
+    NotEnoughCrates {
+        index: usize,
+        instruction: Instruction,
+        stack: usize,
+        available: usize,
+        needed: usize,
+    },
+}
+
+impl ExecError {
+    pub fn index(&self) -> usize {
+        match self {
+            ExecError::NoSuchStack { index, .. } | ExecError::NotEnoughCrates { index, .. } => {
+                *index
+            }
+        }
+    }
+
+    /// The statement that couldn't be carried out.
+    pub fn instruction(&self) -> &Instruction {
+        match self {
+            ExecError::NoSuchStack { instruction, .. }
+            | ExecError::NotEnoughCrates { instruction, .. } => instruction,
+        }
+    }
+
+    /// What was wrong, without saying which instruction it was.
+    pub fn reason(&self) -> String {
+        match self {
+            ExecError::NoSuchStack { stack, stacks, .. } => format!(
+                "there is no stack {}, stacks are numbered 1 to {}",
+                stack, stacks
+            ),
+            ExecError::NotEnoughCrates {
+                stack, available, ..
+            } => format!(
+                "stack {} only holds {} {}, {} short",
+                stack,
+                available,
+                if *available == 1 { "crate" } else { "crates" },
+                self.shortfall()
+            ),
+        }
+    }
+
+    /// How many more crates the instruction needed than its stack held.
+    pub fn shortfall(&self) -> usize {
+        match self {
+            ExecError::NoSuchStack { .. } => 0,
+            ExecError::NotEnoughCrates {
+                available, needed, ..
+            } => needed - available,
+        }
+    }
+}
+
+impl fmt::Display for ExecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "instruction {} ({}): {}",
+            self.index() + 1,
+            self.instruction(),
+            self.reason()
+        )
+    }
+}
+
+impl Error for ExecError {}
+
+/// What lenient execution did with an instruction it couldn't carry out.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Action {
+    Skipped,
+    Clamped { count: usize },
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Adjustment {
+    pub error: ExecError,
+    pub action: Action,
+}
+
+impl fmt::Display for Adjustment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.action {
+            Action::Skipped => write!(f, "{}; skipped", self.error),
+            Action::Clamped { count } => write!(f, "{}; moved {} instead", self.error, count),
+        }
+    }
+}
+
+impl Instruction {
+    /// Checks that this instruction, at `index` in its list, can run against
+    /// `stacks`. A `repeat` body is only checked as it runs.
+    pub fn validate(&self, index: usize, stacks: &[Stack]) -> Result<(), ExecError> {
+        let (used, needed) = match *self {
+            Instruction::Move(Move { count, from, to }) => (vec![from, to], Some((from, count))),
+            Instruction::Swap { a, b } => (vec![a, b], None),
+            Instruction::Reverse { count, stack } => (vec![stack], Some((stack, count))),
+            Instruction::Repeat { .. } => return Ok(()),
+        };
+
+        for stack in used {
+            if stack == 0 || stack > stacks.len() {
+                return Err(ExecError::NoSuchStack {
+                    index,
+                    instruction: self.clone(),
+                    stack,
+                    stacks: stacks.len(),
+                });
+            }
+        }
+
+        if let Some((stack, needed)) = needed {
+            let available = stacks[stack - 1].len();
+            if available < needed {
+                return Err(ExecError::NotEnoughCrates {
+                    index,
+                    instruction: self.clone(),
+                    stack,
+                    available,
+                    needed,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Calls `f` with each statement this instruction carries out, in order,
+    /// running through `repeat` bodies as many times as they repeat. Stops at
+    /// the first error `f` returns.
+    pub fn for_each_statement<E>(
+        &self,
+        f: &mut impl FnMut(&Instruction) -> Result<(), E>,
+    ) -> Result<(), E> {
+        match self {
+            Instruction::Repeat { times, body } => {
+                for _ in 0..*times {
+                    for instruction in body {
+                        instruction.for_each_statement(f)?;
+                    }
+                }
+                Ok(())
+            }
+            statement => f(statement),
+        }
+    }
+
+    /// Checks and runs this instruction. If a statement in a `repeat` can't
+    /// be carried out, the stacks are left as they were.
+    pub fn apply(&self, index: usize, crane: Crane, stacks: &mut [Stack]) -> Result<(), ExecError> {
+        if let Instruction::Repeat { .. } = self {
+            return self.apply_undoable(index, crane, stacks, &mut Vec::new());
+        }
+        self.validate(index, stacks)?;
+        self.run(crane, stacks);
+        Ok(())
+    }
+
+    /// Like `apply`, pushing the statement that takes back each one carried
+    /// out. On an error, the statements already carried out are taken back
+    /// and their inverses popped again.
+    pub(super) fn apply_undoable(
+        &self,
+        index: usize,
+        crane: Crane,
+        stacks: &mut [Stack],
+        undo: &mut Vec<Instruction>,
+    ) -> Result<(), ExecError> {
+        let start = undo.len();
+        let result = self.for_each_statement(&mut |statement| {
+            statement.validate(index, stacks)?;
+            statement.run(crane, stacks);
+            undo.push(statement.inverse());
+            Ok(())
+        });
+        if result.is_err() {
+            for statement in undo.drain(start..).rev() {
+                statement.run(crane, stacks);
+            }
+        }
+        result
+    }
+
+    /// Like `apply`, but skips statements on stacks that don't exist and
+    /// clamps those that need more crates than are available.
+    pub fn apply_lenient(
+        &self,
+        index: usize,
+        crane: Crane,
+        stacks: &mut [Stack],
+        adjustments: &mut Vec<Adjustment>,
+    ) {
+        let Ok(()) = self.for_each_statement(&mut |statement| {
+            match statement.validate(index, stacks) {
+                Ok(()) => statement.run(crane, stacks),
+                Err(error @ ExecError::NoSuchStack { .. }) => adjustments.push(Adjustment {
+                    error,
+                    action: Action::Skipped,
+                }),
+                Err(error @ ExecError::NotEnoughCrates { available, .. }) => {
+                    statement.clamped(available).run(crane, stacks);
+                    adjustments.push(Adjustment {
+                        error,
+                        action: Action::Clamped { count: available },
+                    });
+                }
+            }
+            Ok::<(), Infallible>(())
+        });
+    }
+
+    /// Carries out a validated statement other than `repeat`.
+    pub(super) fn run(&self, crane: Crane, stacks: &mut [Stack]) {
+        match *self {
+            Instruction::Move(ref m) => crane.move_crates(stacks, m),
+            Instruction::Swap { a, b } => stacks.swap(a - 1, b - 1),
+            Instruction::Reverse { count, stack } => {
+                let stack = &mut stacks[stack - 1];
+                let start = stack.len() - count;
+                stack[start..].reverse();
+            }
+            Instruction::Repeat { .. } => unreachable!("repeat is expanded by for_each_statement"),
+        }
+    }
+
+    fn clamped(&self, count: usize) -> Instruction {
+        match *self {
+            Instruction::Move(ref m) => Instruction::Move(Move { count, ..m.clone() }),
+            Instruction::Reverse { stack, .. } => Instruction::Reverse { count, stack },
+            _ => self.clone(),
+        }
+    }
+}
+
+impl Problem {
+    /// Runs every instruction, stopping at the first one that can't be carried out.
+    pub fn execute(&self, crane: Crane) -> Result<Problem, ExecError> {
+        debug!(%crane, instructions = self.instructions.len(), "executing");
+        let mut out = self.clone();
+        for (index, instruction) in self.instructions.iter().enumerate() {
+            instruction.apply(index, crane, &mut out.stacks)?;
+            trace!(index, %instruction, "applied");
+        }
+        Ok(out)
+    }
+
+    /// Runs every instruction, skipping moves between stacks that don't exist
+    /// and clamping moves of more crates than are available.
+    pub fn execute_lenient(&self, crane: Crane) -> (Problem, Vec<Adjustment>) {
+        debug!(%crane, instructions = self.instructions.len(), "executing leniently");
+        let mut out = self.clone();
+        let mut adjustments = Vec::new();
+        for (index, instruction) in self.instructions.iter().enumerate() {
+            instruction.apply_lenient(index, crane, &mut out.stacks, &mut adjustments);
+            trace!(index, %instruction, "applied");
+        }
+        debug!(adjustments = adjustments.len(), "finished");
+        (out, adjustments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day5::{example, problem, stacks_of};
+
+    #[test]
+    fn test_execute_example() {
+        let problem = example();
+        let solved = problem.execute(Crane::CrateMover9000).unwrap();
+        assert_eq!(solved.top_crates(), "CMZ");
+    }
+
+    #[test]
+    fn test_no_such_stack() {
+        let err = problem("move 1 from 2 to 1\nmove 1 from 0 to 1")
+            .execute(Crane::CrateMover9000)
+            .unwrap_err();
+        assert_eq!(err.index(), 1);
+        assert_eq!(
+            err.to_string(),
+            "instruction 2 (move 1 from 0 to 1): there is no stack 0, stacks are numbered 1 to 3"
+        );
+
+        let err = problem("move 1 from 1 to 4")
+            .execute(Crane::CrateMover9000)
+            .unwrap_err();
+        assert!(matches!(err, ExecError::NoSuchStack { stack: 4, .. }));
+    }
+
+    #[test]
+    fn test_not_enough_crates() {
+        let err = problem("move 1 from 3 to 1\nmove 3 from 3 to 1")
+            .execute(Crane::CrateMover9001)
+            .unwrap_err();
+        assert_eq!(err.index(), 1);
+        assert_eq!(err.shortfall(), 3);
+        assert_eq!(
+            err.to_string(),
+            "instruction 2 (move 3 from 3 to 1): stack 3 only holds 0 crates, 3 short"
+        );
+    }
+
+    #[test]
+    fn test_error_inside_repeat() {
+        let err = problem("move 1 from 2 to 1\nrepeat 3 {\n    move 1 from 3 to 2\n}")
+            .execute(Crane::CrateMover9000)
+            .unwrap_err();
+        assert_eq!(err.index(), 1);
+        assert_eq!(
+            err.to_string(),
+            "instruction 2 (move 1 from 3 to 2): stack 3 only holds 0 crates, 1 short"
+        );
+
+        let (solved, adjustments) =
+            problem("reverse 5 on 2\nswap 1 4").execute_lenient(Crane::CrateMover9000);
+        assert_eq!(solved.stacks, stacks_of(&["ZN", "DCM", "P"]));
+        assert_eq!(adjustments[0].action, Action::Clamped { count: 3 });
+        assert_eq!(adjustments[1].action, Action::Skipped);
+    }
+
+    #[test]
+    fn test_failed_repeat_leaves_stacks() {
+        let problem = problem("repeat 3 {\n    move 1 from 1 to 3\n    reverse 2 on 3\n}");
+        let mut stacks = problem.stacks.clone();
+        let err = problem.instructions[0]
+            .apply(0, Crane::CrateMover9000, &mut stacks)
+            .unwrap_err();
+        assert!(matches!(err, ExecError::NotEnoughCrates { stack: 1, .. }));
+        assert_eq!(stacks, problem.stacks);
+    }
+
+    #[test]
+    fn test_execute_lenient() {
+        let problem = problem("move 5 from 2 to 1\nmove 1 from 9 to 1\nmove 1 from 3 to 2");
+        let (solved, adjustments) = problem.execute_lenient(Crane::CrateMover9000);
+
+        assert_eq!(solved.stacks, stacks_of(&["ZNDCM", "P", ""]));
+        assert_eq!(adjustments.len(), 2);
+        assert_eq!(adjustments[0].action, Action::Clamped { count: 3 });
+        assert_eq!(adjustments[0].error.shortfall(), 2);
+        assert_eq!(adjustments[1].action, Action::Skipped);
+        assert_eq!(adjustments[1].error.index(), 1);
+    }
+}
@@ -0,0 +1,265 @@
+use super::{Crate, Instruction, Move, Problem, Stack};
+use std::error::Error;
+use std::fmt;
+
+/// Most stacks `Generator::generate` will draw.
+pub const MAX_STACKS: usize = 1_000;
+
+/// Tallest `Generator::generate` will let a stack start.
+pub const MAX_HEIGHT: usize = 1_000;
+
+/// Most instructions `Generator::generate` will write.
+pub const MAX_INSTRUCTIONS: usize = 1_000_000;
+
+/// Settings for a random puzzle. The same settings always give the same
+/// puzzle.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Generator {
+    pub seed: u64,
+    pub stacks: usize,
+    /// Tallest a stack can start
+    pub height: usize,
+    pub instructions: usize,
+}
+
+impl Default for Generator {
+    /// Sized like a real puzzle input.
+    fn default() -> Generator {
+        Generator {
+            seed: 0,
+            stacks: 9,
+            height: 8,
+            instructions: 500,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum GenerateError {
+    NoStacks,
+    NoCrates,
+    TooManyStacks,
+    TooTall,
+    TooManyInstructions,
+}
+
+impl fmt::Display for GenerateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GenerateError::NoStacks => write!(f, "a puzzle needs at least one stack"),
+            GenerateError::NoCrates => {
+                write!(
+                    f,
+                    "instructions need crates to move, so height must be at least 1"
+                )
+            }
+            GenerateError::TooManyStacks => {
+                write!(f, "a puzzle can have at most {} stacks", MAX_STACKS)
+            }
+            GenerateError::TooTall => {
+                write!(f, "stacks can start at most {} crates tall", MAX_HEIGHT)
+            }
+            GenerateError::TooManyInstructions => {
+                write!(
+                    f,
+                    "a puzzle can have at most {} instructions",
+                    MAX_INSTRUCTIONS
+                )
+            }
+        }
+    }
+}
+
+impl Error for GenerateError {}
+
+impl Generator {
+    /// Builds a random drawing and instruction list. Every instruction moves
+    /// between existing stacks and never takes more crates than its stack
+    /// holds, whichever crane runs it. Settings past `MAX_STACKS`,
+    /// `MAX_HEIGHT` or `MAX_INSTRUCTIONS` are refused.
+    pub fn generate(&self) -> Result<Problem, GenerateError> {
+        if self.stacks == 0 {
+            return Err(GenerateError::NoStacks);
+        }
+        if self.stacks > MAX_STACKS {
+            return Err(GenerateError::TooManyStacks);
+        }
+        if self.height > MAX_HEIGHT {
+            return Err(GenerateError::TooTall);
+        }
+        if self.instructions > MAX_INSTRUCTIONS {
+            return Err(GenerateError::TooManyInstructions);
+        }
+        if self.height == 0 && self.instructions > 0 {
+            return Err(GenerateError::NoCrates);
+        }
+
+        let mut rng = SplitMix64(self.seed);
+        let mut stacks = (0..self.stacks)
+            .map(|_| {
+                let height = rng.below(self.height + 1);
+                (0..height).map(|_| rng.label()).collect::<Stack>()
+            })
+            .collect::<Vec<Stack>>();
+        if self.height > 0 && stacks.iter().all(Vec::is_empty) {
+            stacks[0].push(rng.label());
+        }
+
+        // Only the heights matter while choosing moves
+        let mut heights = stacks.iter().map(Vec::len).collect::<Vec<usize>>();
+        let mut instructions = Vec::with_capacity(self.instructions);
+        for _ in 0..self.instructions {
+            let loaded = (0..heights.len())
+                .filter(|&i| heights[i] > 0)
+                .collect::<Vec<usize>>();
+            let from = loaded[rng.below(loaded.len())];
+            let to = match heights.len() {
+                1 => from,
+                n => (from + 1 + rng.below(n - 1)) % n,
+            };
+            let count = 1 + rng.below(heights[from]);
+
+            heights[from] -= count;
+            heights[to] += count;
+            instructions.push(Instruction::Move(Move {
+                count,
+                from: from + 1,
+                to: to + 1,
+            }));
+        }
+
+        Ok(Problem {
+            stacks,
+            instructions,
+        })
+    }
+}
+
+/// Small, fast generator with a stable sequence for every seed, so puzzles
+/// can be shared as their settings.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number from `0..n`; `n` must not be 0.
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn label(&mut self) -> Crate {
+        let letter = (b'A' + self.below(26) as u8) as char;
+        Crate::new(&letter.to_string()).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day5::Crane;
+
+    #[test]
+    fn test_generated_puzzles_run() {
+        for seed in 0..50 {
+            let generator = Generator {
+                seed,
+                stacks: 1 + seed as usize % 12,
+                height: 1 + seed as usize % 5,
+                instructions: 200,
+            };
+            let problem = generator.generate().unwrap();
+            assert_eq!(problem.stacks.len(), generator.stacks);
+            assert!(problem.stacks.iter().all(|s| s.len() <= generator.height));
+            assert_eq!(problem.instructions.len(), 200);
+
+            let text = problem.to_string();
+            assert_eq!(text.parse::<Problem>().unwrap(), problem);
+            for crane in [Crane::CrateMover9000, Crane::CrateMover9001] {
+                assert!(problem.execute(crane).is_ok(), "seed {}", seed);
+            }
+        }
+    }
+
+    #[test]
+    fn test_seeds() {
+        let generator = Generator::default();
+        assert_eq!(generator.generate(), generator.generate());
+
+        let other = Generator {
+            seed: 1,
+            ..Generator::default()
+        };
+        assert_ne!(generator.generate(), other.generate());
+    }
+
+    #[test]
+    fn test_generate_errors() {
+        let generator = Generator {
+            stacks: 0,
+            ..Generator::default()
+        };
+        assert_eq!(generator.generate(), Err(GenerateError::NoStacks));
+
+        let generator = Generator {
+            height: 0,
+            ..Generator::default()
+        };
+        assert_eq!(generator.generate(), Err(GenerateError::NoCrates));
+
+        let generator = Generator {
+            height: 0,
+            instructions: 0,
+            ..Generator::default()
+        };
+        assert!(generator
+            .generate()
+            .unwrap()
+            .stacks
+            .iter()
+            .all(Vec::is_empty));
+    }
+
+    #[test]
+    fn test_generate_limits() {
+        let too_big = [
+            (
+                Generator {
+                    stacks: MAX_STACKS + 1,
+                    ..Generator::default()
+                },
+                GenerateError::TooManyStacks,
+            ),
+            (
+                Generator {
+                    height: usize::MAX,
+                    ..Generator::default()
+                },
+                GenerateError::TooTall,
+            ),
+            (
+                Generator {
+                    instructions: usize::MAX,
+                    ..Generator::default()
+                },
+                GenerateError::TooManyInstructions,
+            ),
+        ];
+        for (generator, err) in too_big {
+            assert_eq!(generator.generate(), Err(err));
+        }
+
+        let largest = Generator {
+            stacks: MAX_STACKS,
+            height: MAX_HEIGHT,
+            instructions: 10,
+            ..Generator::default()
+        };
+        assert_eq!(largest.generate().unwrap().stacks.len(), MAX_STACKS);
+    }
+}
@@ -0,0 +1,254 @@
+use super::{Crane, ExecError, Instruction, Move, Problem, Stack};
+
+/// An instruction that has been carried out, with the statements that put
+/// the stacks back the way they were, in the order they must run.
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Applied {
+    instruction: Instruction,
+    undo: Vec<Instruction>,
+}
+
+/// Runs instructions against a set of stacks, remembering each one so it can
+/// be undone and redone.
+#[derive(Debug, Clone)]
+pub struct History {
+    crane: Crane,
+    stacks: Vec<Stack>,
+    /// The problem's own instructions, followed by `replay_to`
+    script: Vec<Instruction>,
+    done: Vec<Applied>,
+    undone: Vec<Applied>,
+}
+
+impl History {
+    pub fn new(stacks: Vec<Stack>, crane: Crane) -> History {
+        History {
+            crane,
+            stacks,
+            script: Vec::new(),
+            done: Vec::new(),
+            undone: Vec::new(),
+        }
+    }
+
+    pub fn stacks(&self) -> &[Stack] {
+        &self.stacks
+    }
+
+    /// Number of instructions currently applied.
+    pub fn step(&self) -> usize {
+        self.done.len()
+    }
+
+    /// The instructions currently applied, oldest first.
+    pub fn applied(&self) -> impl Iterator<Item = &Instruction> {
+        self.done.iter().map(|applied| &applied.instruction)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.done.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    /// Runs `instruction` and forgets anything that was undone. If it fails
+    /// partway through a `repeat`, the stacks are left as they were.
+    pub fn apply(&mut self, instruction: Instruction) -> Result<(), ExecError> {
+        let mut undo = Vec::new();
+        instruction.apply_undoable(self.done.len(), self.crane, &mut self.stacks, &mut undo)?;
+
+        undo.reverse();
+        self.done.push(Applied { instruction, undo });
+        self.undone.clear();
+        Ok(())
+    }
+
+    /// Takes back the last instruction, returning it.
+    pub fn undo(&mut self) -> Option<&Instruction> {
+        let applied = self.done.pop()?;
+        for instruction in &applied.undo {
+            instruction.run(self.crane, &mut self.stacks);
+        }
+        self.undone.push(applied);
+        self.undone.last().map(|applied| &applied.instruction)
+    }
+
+    /// Puts back the last undone instruction, returning it.
+    pub fn redo(&mut self) -> Option<&Instruction> {
+        let applied = self.undone.pop()?;
+        // It ran from exactly this state before, so it can't fail now
+        let mut undo = Vec::new();
+        applied
+            .instruction
+            .apply_undoable(self.done.len(), self.crane, &mut self.stacks, &mut undo)
+            .unwrap_or_else(|e| panic!("redo failed: {}", e));
+        self.done.push(applied);
+        self.done.last().map(|applied| &applied.instruction)
+    }
+
+    /// Moves to the state after `step` of the problem's instructions. First
+    /// undoes back to where the applied instructions last matched the
+    /// problem, or to `step` if that's earlier. Going forwards then redoes
+    /// what was undone while it still matches the problem, and runs the
+    /// problem's instructions after that. Steps past the end stop at the last
+    /// instruction.
+    pub fn replay_to(&mut self, step: usize) -> Result<(), ExecError> {
+        let matching = self
+            .done
+            .iter()
+            .zip(&self.script)
+            .take_while(|(applied, instruction)| applied.instruction == **instruction)
+            .count();
+        while self.done.len() > step.min(matching) {
+            self.undo();
+        }
+        while self.done.len() < step.min(self.script.len()) {
+            let next = &self.script[self.done.len()];
+            match self.undone.last() {
+                Some(applied) if applied.instruction == *next => {
+                    self.redo();
+                }
+                _ => self.apply(next.clone())?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Instruction {
+    /// The statement that takes back this one. Moving crates back with the
+    /// same crane restores their order, and swaps and reversals undo
+    /// themselves. A `repeat` has no single inverse, so it comes back as is.
+    pub fn inverse(&self) -> Instruction {
+        match *self {
+            Instruction::Move(Move { count, from, to }) => Instruction::Move(Move {
+                count,
+                from: to,
+                to: from,
+            }),
+            _ => self.clone(),
+        }
+    }
+}
+
+impl Problem {
+    /// A history starting from this problem's stacks, which `replay_to` steps
+    /// through this problem's instructions.
+    pub fn history(&self, crane: Crane) -> History {
+        History {
+            script: self.instructions.clone(),
+            ..History::new(self.stacks.clone(), crane)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day5::problem;
+
+    /// The example's moves, then one of each other kind of instruction.
+    fn script() -> Problem {
+        problem(
+            "move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2
+swap 1 3
+reverse 3 on 1
+repeat 2 {
+    move 1 from 1 to 2
+    reverse 2 on 2
+}",
+        )
+    }
+
+    #[test]
+    fn test_undo_apply() {
+        let problem = script();
+        for crane in [Crane::CrateMover9000, Crane::CrateMover9001] {
+            let mut history = problem.history(crane);
+            for instruction in &problem.instructions {
+                let before = history.stacks().to_vec();
+                history.apply(instruction.clone()).unwrap();
+                assert_ne!(history.stacks(), before.as_slice());
+
+                assert_eq!(history.undo(), Some(instruction));
+                assert_eq!(history.stacks(), before.as_slice());
+
+                assert_eq!(history.redo(), Some(instruction));
+            }
+            assert_eq!(
+                history.stacks(),
+                problem.execute(crane).unwrap().stacks.as_slice()
+            );
+
+            while history.undo().is_some() {}
+            assert_eq!(history.stacks(), problem.stacks.as_slice());
+        }
+    }
+
+    #[test]
+    fn test_apply_clears_redo() {
+        let problem = script();
+        let mut history = problem.history(Crane::CrateMover9000);
+        history.replay_to(2).unwrap();
+        history.undo();
+        assert!(history.can_redo());
+
+        history.apply("swap 1 2".parse().unwrap()).unwrap();
+        assert!(!history.can_redo());
+        assert_eq!(history.redo(), None);
+        assert_eq!(history.step(), 2);
+    }
+
+    #[test]
+    fn test_failed_apply_leaves_stacks() {
+        let problem = script();
+        let mut history = problem.history(Crane::CrateMover9000);
+        let instruction = "repeat 5 {\n    move 1 from 1 to 2\n}".parse().unwrap();
+
+        let err = history.apply(instruction).unwrap_err();
+        assert_eq!(err.index(), 0);
+        assert_eq!(history.stacks(), problem.stacks.as_slice());
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn test_replay_after_apply() {
+        let problem = script();
+        let mut history = problem.history(Crane::CrateMover9000);
+        let mut sim = problem.simulate(Crane::CrateMover9000);
+
+        history.replay_to(2).unwrap();
+        history.undo();
+        history.apply("swap 1 2".parse().unwrap()).unwrap();
+        history.replay_to(4).unwrap();
+        sim.seek(4).unwrap();
+        assert_eq!(history.stacks(), sim.stacks());
+        assert!(history.applied().eq(&problem.instructions[..4]));
+
+        history.apply("swap 1 3".parse().unwrap()).unwrap();
+        history.replay_to(2).unwrap();
+        sim.seek(2).unwrap();
+        assert_eq!(history.stacks(), sim.stacks());
+        assert_eq!(history.step(), 2);
+    }
+
+    #[test]
+    fn test_replay_to() {
+        let problem = script();
+        let mut history = problem.history(Crane::CrateMover9001);
+        let mut sim = problem.simulate(Crane::CrateMover9001);
+
+        for step in [3, 1, 6, 6, 0, 7, 2, 100] {
+            history.replay_to(step).unwrap();
+            sim.seek(step).unwrap();
+            assert_eq!(history.stacks(), sim.stacks());
+            assert_eq!(history.step(), sim.step());
+        }
+        assert_eq!(history.applied().count(), problem.instructions.len());
+    }
+}
@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A crate's label, the text between its brackets in a drawing. Labels can be
+/// any number of letters or digits, so `[AB]` and `[7]` are both crates.
+/// Serialized as the bare label.
+#[derive(Debug, PartialEq, Eq, Clone, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Crate(Box<str>);
+
+impl Crate {
+    /// Returns `None` unless `label` is one or more letters or digits.
+    pub fn new(label: &str) -> Option<Crate> {
+        match !label.is_empty() && label.chars().all(char::is_alphanumeric) {
+            true => Some(Crate(label.into())),
+            false => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Width of the label in characters, not counting the brackets.
+    pub fn width(&self) -> usize {
+        self.0.chars().count()
+    }
+}
+
+impl TryFrom<String> for Crate {
+    type Error = String;
+
+    fn try_from(label: String) -> Result<Crate, String> {
+        Crate::new(&label).ok_or_else(|| format!("'{}' is not a valid crate label", label))
+    }
+}
+
+impl From<Crate> for String {
+    fn from(c: Crate) -> String {
+        c.0.into()
+    }
+}
+
+impl fmt::Display for Crate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        assert_eq!(Crate::new("AB").unwrap().as_str(), "AB");
+        assert_eq!(Crate::new("Ä7").unwrap().width(), 2);
+        assert_eq!(Crate::new(""), None);
+        assert_eq!(Crate::new("A B"), None);
+        assert_eq!(Crate::new("#"), None);
+    }
+}
@@ -1,241 +1,369 @@
-/*
-Crate Label ->          [D]
-Crate Label ->      [N] [C]
-Crate Label ->      [Z] [M] [P]
-Stack num ->         1   2   3
-
-Instruction ->    move 1 from 2 to 1
-Instruction ->    move 3 from 1 to 3
-Instruction ->    move 2 from 2 to 1
-Instruction ->    move 1 from 1 to 2
-*/
-
-use lazy_static::lazy_static;
-use regex::Regex;
-use std::{collections::HashMap, usize};
-use std::fs;
-
-type Crate = char;
-type Stack = Vec<Crate>;
-
-#[derive(Debug, PartialEq, Eq, Clone)]
-struct Instruction {
-    count: usize,
-    from: usize,
-    to: usize,
+use advent2022::day5::{
+    render_highlighted, top_crates, Control, CostModel, Crane, Day5, ExecError, Generator,
+    Highlight, Player, Problem, Repl, Reply, Simulation,
+};
+use advent2022::input;
+use advent2022::logging;
+use advent2022::Solution;
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use std::io::{self, IsTerminal, Write};
+use std::process;
+use std::time::Duration;
+
+const USAGE: &str = "Usage: day5 [-v|-vv] [--lenient] [--step <N>] [input]
+       day5 [-v|-vv] --stats [--move-cost <N>] [--crate-cost <N>] [input]
+       day5 [-v|-vv] --animate [--crane <9000|9001>] [--delay <ms>] [input]
+       day5 [-v|-vv] --trace <json|binary> [--crane <9000|9001>] [input]
+       day5 [-v|-vv] --repl [--crane <9000|9001>] [drawing]
+       day5 --generate [--seed <N>] [--stacks <N>] [--height <N>] [--moves <N>]";
+
+struct Args {
+    input: Option<String>,
+    lenient: bool,
+    step: Option<usize>,
+    animate: bool,
+    repl: bool,
+    stats: Option<CostModel>,
+    crane: Crane,
+    delay: Duration,
+    generate: Option<Generator>,
+    trace: Option<String>,
 }
 
-impl Instruction {
-    fn from_str(input_str: &str) -> Self {
-        lazy_static! {
-            static ref INST_RE: Regex = Regex::new(r"^move (\d+) from (\d+) to (\d+)$").unwrap();
-        }
-
-        let cap = INST_RE.captures_iter(input_str).next().unwrap();
-        println!("Num: {}, From: {}, To: {}", &cap[1], &cap[2], &cap[3]);
-
-        let count = cap[1].parse::<usize>().unwrap();
-        let from = cap[2].parse::<usize>().unwrap();
-        let to = cap[3].parse::<usize>().unwrap();
+fn usage_error(message: &str) -> ! {
+    eprintln!("error: {}\n\n{}", message, USAGE);
+    process::exit(2);
+}
 
-        Instruction { count, from, to }
+fn parse_args() -> Args {
+    let mut args = Args {
+        input: None,
+        lenient: false,
+        step: None,
+        animate: false,
+        repl: false,
+        stats: None,
+        crane: Crane::CrateMover9000,
+        delay: Duration::from_millis(200),
+        generate: None,
+        trace: None,
+    };
+    let mut argv = logging::init_from_args().into_iter();
+    while let Some(arg) = argv.next() {
+        match arg.as_str() {
+            "--lenient" => args.lenient = true,
+            "--animate" => args.animate = true,
+            "--repl" => args.repl = true,
+            "--step" => match argv.next().and_then(|n| n.parse().ok()) {
+                Some(step) => args.step = Some(step),
+                None => usage_error("--step needs a number"),
+            },
+            "--crane" => match argv.next().as_deref().and_then(Crane::from_model) {
+                Some(crane) => args.crane = crane,
+                None => usage_error("--crane must be 9000 or 9001"),
+            },
+            "--delay" => match argv.next().and_then(|n| n.parse().ok()) {
+                Some(ms) => args.delay = Duration::from_millis(ms),
+                None => usage_error("--delay needs a number of milliseconds"),
+            },
+            "--trace" => match argv.next() {
+                Some(format) if format == "json" || format == "binary" => args.trace = Some(format),
+                _ => usage_error("--trace must be json or binary"),
+            },
+            "--stats" => args.stats = Some(args.stats.unwrap_or_default()),
+            "--move-cost" | "--crate-cost" => {
+                let Some(n) = argv.next().and_then(|n| n.parse().ok()) else {
+                    usage_error(&format!("{} needs a number", arg));
+                };
+                let costs = args.stats.get_or_insert_with(CostModel::default);
+                match arg.as_str() {
+                    "--move-cost" => costs.per_move = n,
+                    _ => costs.per_crate = n,
+                }
+            }
+            "--generate" => args.generate = Some(args.generate.unwrap_or_default()),
+            "--seed" | "--stacks" | "--height" | "--moves" => {
+                let Some(n) = argv.next().and_then(|n| n.parse().ok()) else {
+                    usage_error(&format!("{} needs a number", arg));
+                };
+                let generator = args.generate.get_or_insert_with(Generator::default);
+                match arg.as_str() {
+                    "--seed" => generator.seed = n,
+                    // Anything too big for a usize is past the generator's limits anyway
+                    "--stacks" => generator.stacks = usize::try_from(n).unwrap_or(usize::MAX),
+                    "--height" => generator.height = usize::try_from(n).unwrap_or(usize::MAX),
+                    _ => generator.instructions = usize::try_from(n).unwrap_or(usize::MAX),
+                }
+            }
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            _ => args.input = Some(arg),
+        }
     }
+    args
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
-struct Problem {
-    stacks: Vec<Stack>,
-    instructions: Vec<Instruction>,
+fn load_problem_from_file(arg: Option<&str>) -> String {
+    input::load_input(Day5::DAY, arg).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(1);
+    })
 }
 
-trait StackExt {
-    fn from_str(input_str: &str) -> Stack;
+/// Prints `e` pointing at its instruction in `input`, then exits.
+fn exec_error(input: &str, e: &ExecError) -> ! {
+    eprintln!("{}", Problem::locate_exec_error(input, e).render());
+    process::exit(1);
 }
 
-impl StackExt for Stack {
-    fn from_str(input_str: &str) -> Stack {
-        todo!()
+fn run(input: &str, problem: &Problem, crane: Crane, lenient: bool) -> Problem {
+    if !lenient {
+        return problem
+            .execute(crane)
+            .unwrap_or_else(|e| exec_error(input, &e));
+    }
+
+    let (solved, adjustments) = problem.execute_lenient(crane);
+    for adjustment in adjustments {
+        eprintln!("warning: {}: {}", crane, adjustment);
     }
+    solved
 }
 
-impl Problem {
-    fn from_str(input_str: &str) -> Self {
-        let mut splits = input_str.split("\n\n");
-        let stacks_str = splits.next().unwrap();
-        let instructions_str = splits.next().unwrap();
+fn show_step(input: &str, problem: &Problem, crane: Crane, step: usize) {
+    let mut sim = problem.simulate(crane);
+    if let Err(e) = sim.seek(step) {
+        exec_error(input, &e);
+    }
+    println!("{}\n{}\n", crane, sim.snapshot());
+}
 
-        let instructions = instructions_str
-            .split('\n')
-            .map(Instruction::from_str)
-            .collect::<Vec<Instruction>>();
+/// Puts the terminal into raw mode on an alternate screen, restoring it on drop.
+struct Terminal;
 
-        let stacks = Problem::stacks_from_str(stacks_str);
-        Problem {
-            stacks,
-            instructions,
-        }
+impl Terminal {
+    fn enter() -> io::Result<Terminal> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen, Hide)?;
+        Ok(Terminal)
     }
+}
 
-    fn stacks_from_str(input_str: &str) -> Vec<Stack> {
-        /*
-        Expected input string ex
-
-            [D]
-        [N] [C]
-        [Z] [M] [P]
-         1   2   3
-        */
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
 
-        let mut out: Vec<Stack> = Vec::new();
+/// Waits up to `timeout` (forever if `None`) for a key press.
+fn next_key(timeout: Option<Duration>) -> io::Result<Option<char>> {
+    loop {
+        if let Some(timeout) = timeout {
+            if !event::poll(timeout)? {
+                return Ok(None);
+            }
+        }
+        if let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press,
+            ..
+        }) = event::read()?
+        {
+            let key = match code {
+                KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => 'q',
+                KeyCode::Char(c) => c,
+                KeyCode::Right => 'n',
+                KeyCode::Esc => 'q',
+                _ => continue,
+            };
+            return Ok(Some(key));
+        }
+    }
+}
 
-        let mut split = input_str.split('\n').rev();
-        let idx_line_chars = split.next().unwrap().chars();
+fn draw(
+    sim: &Simulation,
+    crane: Crane,
+    highlight: Option<Highlight>,
+    player: &Player,
+    status: &str,
+) -> io::Result<()> {
+    let mut out = io::stdout();
+    queue!(out, MoveTo(0, 0), Clear(ClearType::All))?;
+
+    let snapshot = sim.snapshot();
+    match &snapshot.instruction {
+        Some(instruction) => write!(out, "{} - step {}: {}", crane, snapshot.step, instruction)?,
+        None => write!(out, "{} - step 0", crane)?,
+    }
+    write!(out, "\r\n\r\n")?;
+    for line in render_highlighted(sim.stacks(), highlight) {
+        write!(out, "{}\r\n", line)?;
+    }
+    write!(out, "\r\n{}\r\n{}", status, player.help())?;
+    out.flush()
+}
 
-        let v_ = idx_line_chars.clone();
+fn animate(problem: &Problem, crane: Crane, delay: Duration) -> io::Result<()> {
+    let _terminal = Terminal::enter()?;
+    let mut sim = problem.simulate(crane);
+    let mut player = Player::new(delay);
+    let mut highlight = None;
+    let mut status = String::new();
 
-        let stack_idx = idx_line_chars
-            .filter_map(|c| match c.is_ascii_digit() {
-                true => Some(v_.clone().position(|c_| c == c_).unwrap()),
-                false => None,
-            })
-            .collect::<Vec<usize>>();
+    loop {
+        draw(&sim, crane, highlight, &player, &status)?;
 
-        let mut stacks_by_id: HashMap<usize, usize> = HashMap::new();
-        for (i, s_id) in stack_idx.iter().enumerate() {
-            out.push(Vec::new());
-            stacks_by_id.insert(*s_id, i);
+        let timeout = match player.paused || sim.is_finished() {
+            true => None,
+            false => Some(player.delay),
+        };
+        let step = match next_key(timeout)? {
+            Some(key) => match player.handle_key(key) {
+                Control::Quit => return Ok(()),
+                Control::Step => true,
+                Control::Continue => false,
+            },
+            None => true,
+        };
+        if !step {
+            continue;
         }
 
-        for stack_line in split {
-            for idx in &stack_idx {
-                let s = stack_line.chars().nth(*idx).unwrap();
-                if s.is_alphabetic() {
-                    out[*stacks_by_id.get(idx).unwrap()].push(s);
-                };
-            }
+        match sim.advance() {
+            Some(Ok(instruction)) => highlight = Highlight::after(instruction),
+            Some(Err(e)) => status = format!("error: {}", e),
+            None => {}
+        }
+        if sim.is_finished() && status.is_empty() {
+            status = format!("Finished, top crates: {}", top_crates(sim.stacks()));
         }
-        out
     }
+}
 
-    fn solve(&self) -> Problem {
-        let mut out = self.clone();
-        for instruction in &self.instructions {
-
-            let mut t_stack = Vec::new();
-            for _ in 0..instruction.count {
-                let v = out.stacks[instruction.from - 1].pop().unwrap();
-                t_stack.push(v);
-            }
-            for v in t_stack.iter().rev() {
-                out.stacks[instruction.to - 1].push(*v)
+/// Reads commands from stdin until `quit` or end of input.
+fn repl(mut repl: Repl) -> io::Result<()> {
+    let interactive = io::stdin().is_terminal();
+    if interactive {
+        for command in ["help", "show"] {
+            if let Reply::Print(text) = repl.handle(command) {
+                println!("{}\n", text);
             }
         }
-
-        out
     }
-
-    fn display_answer(&self) {
-        print!("Answer: ");
-        for stack in &self.stacks {
-            print!("{}", stack.last().unwrap());
+    let mut line = String::new();
+    loop {
+        if interactive {
+            print!("{}", repl.prompt());
+            io::stdout().flush()?;
+        }
+        line.clear();
+        if io::stdin().read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        match repl.handle(&line) {
+            Reply::Print(text) => println!("{}", text),
+            Reply::More => {}
+            Reply::Quit => return Ok(()),
         }
-        print!("\n");
     }
 }
 
-
-fn load_problem_from_file() -> Problem {
-    let raw_string = fs::read_to_string("/home/cole/rust/advent2022/src/day5/input.txt")
-        .expect("Failed to read input file");
-
-    Problem::from_str(raw_string.as_str())
-}
-fn main() {
-    let problem = load_problem_from_file();
-    let problem = problem.solve();
-    problem.display_answer();
-
+/// Prints every frame in turn, for when stdout isn't a terminal.
+fn dump_frames(input: &str, problem: &Problem, crane: Crane) {
+    let mut sim = problem.simulate(crane);
+    println!("{}\n{}\n", crane, sim.snapshot());
+    for snapshot in &mut sim {
+        match snapshot {
+            Ok(snapshot) => println!("{}\n", snapshot),
+            Err(e) => exec_error(input, &e),
+        }
+    }
 }
 
-#[cfg(test)]
-mod tests {
-
-    use super::*;
-
-    #[test]
-    fn test_instruction_from_str() {
-        let input_str = "move 1 from 2 to 1";
-        let actual = Instruction::from_str(input_str);
-        let expected = Instruction {
-            count: 1,
-            from: 2,
-            to: 1,
-        };
-        assert_eq!(actual, expected);
-
-        let input_str = "move 3 from 1 to 3";
-        let actual = Instruction::from_str(input_str);
-        let expected = Instruction {
-            count: 3,
-            from: 1,
-            to: 3,
-        };
-        assert_eq!(actual, expected);
-
-        let input_str = "move 2 from 2 to 1";
-        let actual = Instruction::from_str(input_str);
-        let expected = Instruction {
-            count: 2,
-            from: 2,
-            to: 1,
-        };
-        assert_eq!(actual, expected);
-
-        let input_str = "move 1 from 1 to 2";
-        let actual = Instruction::from_str(input_str);
-        let expected = Instruction {
-            count: 1,
-            from: 1,
-            to: 2,
-        };
-        assert_eq!(actual, expected);
-    }
-
-    #[test]
-    fn test_construct_problem() {
-        let input_str = r#"    [D]    
-[N] [C]    
-[Z] [M] [P]
- 1   2   3 
-
-move 1 from 2 to 1"#;
-        println!("{}", input_str);
-        let expected = Problem {
-            stacks: vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']],
-            instructions: vec![Instruction {
-                count: 1,
-                from: 2,
-                to: 1,
-            }],
+fn main() {
+    let args = parse_args();
+    if let Some(generator) = &args.generate {
+        match generator.generate() {
+            Ok(problem) => print!("{}", problem),
+            Err(e) => {
+                eprintln!("error: {}", e);
+                process::exit(1);
+            }
+        }
+        return;
+    }
+    if args.repl {
+        let input = load_problem_from_file(args.input.as_deref());
+        let session = Repl::parse(&input, args.crane).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            process::exit(1);
+        });
+        if let Err(e) = repl(session) {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        }
+        return;
+    }
+    // Parsed without checking the instructions, which --lenient, --step and
+    // --animate each deal with in their own way
+    let input = load_problem_from_file(args.input.as_deref());
+    let problem = input.parse::<Problem>().unwrap_or_else(|e| {
+        eprintln!("{}", e.render());
+        process::exit(1);
+    });
+
+    if let Some(format) = &args.trace {
+        let trace = problem
+            .trace(args.crane)
+            .unwrap_or_else(|e| exec_error(&input, &e));
+        let written = match format.as_str() {
+            "json" => writeln!(io::stdout(), "{}", trace.to_json()),
+            _ => io::stdout().write_all(&trace.to_bytes()),
         };
+        if let Err(e) = written {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        }
+        return;
+    }
 
-        let actual = Problem::from_str(input_str);
-        assert_eq!(expected, actual);
+    if args.animate {
+        if !io::stdout().is_terminal() {
+            dump_frames(&input, &problem, args.crane);
+        } else if let Err(e) = animate(&problem, args.crane, args.delay) {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        }
+        return;
     }
 
-    #[test]
-    fn test_solve() {
-        let input_str = r#"    [D]    
-[N] [C]    
-[Z] [M] [P]
- 1   2   3 
+    if let Some(step) = args.step {
+        for crane in [Crane::CrateMover9000, Crane::CrateMover9001] {
+            show_step(&input, &problem, crane, step);
+        }
+        return;
+    }
 
-move 1 from 2 to 1
-move 3 from 1 to 3
-move 2 from 2 to 1
-move 1 from 1 to 2"#;
-        let problem = Problem::from_str(input_str).solve();
-        problem.display_answer();
+    if let Some(costs) = &args.stats {
+        for crane in [Crane::CrateMover9000, Crane::CrateMover9001] {
+            let (solved, stats) = problem
+                .execute_with_stats(crane, costs)
+                .unwrap_or_else(|e| exec_error(&input, &e));
+            println!("{}: {}\n{}\n", crane, solved.top_crates(), stats);
+        }
+        return;
+    }
 
+    for crane in [Crane::CrateMover9000, Crane::CrateMover9001] {
+        let solved = run(&input, &problem, crane, args.lenient);
+        println!("{}: {}", crane, solved.top_crates());
     }
 }
@@ -0,0 +1,544 @@
+/*
+Crate Label ->          [D]
+Crate Label ->      [N] [C]
+Crate Label ->      [Z] [M] [P]
+Stack num ->         1   2   3
+
+Instruction ->    move 1 from 2 to 1
+Instruction ->    move 3 from 1 to 3
+Instruction ->    move 2 from 2 to 1
+Instruction ->    move 1 from 1 to 2
+*/
+
+use crate::input;
+use crate::{ParseError, Solution};
+use lazy_static::lazy_static;
+use regex::Regex;
+use script::Location;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::Deref;
+use std::str::FromStr;
+use tracing::{debug, trace};
+
+mod animate;
+mod crane;
+mod drawing;
+mod exec;
+mod generate;
+mod history;
+mod label;
+mod plan;
+mod repl;
+mod script;
+mod sim;
+mod stats;
+mod trace;
+
+pub use animate::{render_highlighted, Control, Highlight, Player};
+pub use crane::Crane;
+pub use drawing::{parse_drawing, render_drawing, StackExt};
+pub use exec::{Action, Adjustment, ExecError};
+pub use generate::{GenerateError, Generator, MAX_HEIGHT, MAX_INSTRUCTIONS, MAX_STACKS};
+pub use history::History;
+pub use label::Crate;
+pub use plan::{plan, PlanError, MAX_STATES};
+pub use repl::{Repl, Reply};
+pub use script::{parse_instructions, MAX_STATEMENTS};
+pub use sim::{Simulation, Snapshot};
+pub use stats::{CostModel, RunStats, Traffic};
+pub use trace::Trace;
+
+pub type Stack = Vec<Crate>;
+
+/// The puzzle's answer: the label on top of each stack, skipping empty ones.
+pub fn top_crates(stacks: &[Stack]) -> String {
+    stacks
+        .iter()
+        .filter_map(|stack| stack.last())
+        .map(Crate::as_str)
+        .collect()
+}
+
+/// Builds stacks of single-letter crates, one string per stack listed bottom
+/// to top.
+#[cfg(test)]
+pub(crate) fn stacks_of(columns: &[&str]) -> Vec<Stack> {
+    columns
+        .iter()
+        .map(|column| {
+            column
+                .chars()
+                .map(|c| Crate::new(&c.to_string()).unwrap())
+                .collect()
+        })
+        .collect()
+}
+
+/// The drawing from the puzzle page with `instructions` under it.
+#[cfg(test)]
+pub(crate) fn problem(instructions: &str) -> Problem {
+    format!(
+        "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n\n{}",
+        instructions
+    )
+    .parse()
+    .unwrap()
+}
+
+/// The puzzle page's example, drawing and moves.
+#[cfg(test)]
+pub(crate) fn example() -> Problem {
+    problem("move 1 from 2 to 1\nmove 3 from 1 to 3\nmove 2 from 2 to 1\nmove 1 from 1 to 2")
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: u8 = 5;
+
+    type Input = CheckedProblem;
+    type Answer1 = String;
+    type Answer2 = String;
+
+    /// Also checks that every instruction can be carried out, pointing at
+    /// the first one that can't.
+    fn parse(input: &str) -> Result<CheckedProblem, ParseError> {
+        let (problem, locations) = Problem::parse_located(input)?;
+        // Whether a statement can run only depends on how many crates each
+        // stack holds, which is the same whichever crane does the moving
+        if let Err(e) = problem.execute(Crane::CrateMover9000) {
+            return Err(problem.exec_parse_error(&locations, &e));
+        }
+        Ok(CheckedProblem(problem))
+    }
+
+    fn part1(problem: &CheckedProblem) -> String {
+        problem.solved(Crane::CrateMover9000).top_crates()
+    }
+
+    fn part2(problem: &CheckedProblem) -> String {
+        problem.solved(Crane::CrateMover9001).top_crates()
+    }
+}
+
+/// A problem whose instructions can all be carried out. Only `Day5::parse`
+/// makes one, so the parts can run it without handling an `ExecError`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CheckedProblem(Problem);
+
+impl CheckedProblem {
+    pub fn into_problem(self) -> Problem {
+        self.0
+    }
+
+    fn solved(&self, crane: Crane) -> Problem {
+        self.0
+            .execute(crane)
+            .unwrap_or_else(|e| unreachable!("Day5::parse checked every instruction: {}", e))
+    }
+}
+
+impl Deref for CheckedProblem {
+    type Target = Problem;
+
+    fn deref(&self) -> &Problem {
+        &self.0
+    }
+}
+
+/// A single crane move, the only instruction the puzzle itself uses.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct Move {
+    pub count: usize,
+    pub from: usize,
+    pub to: usize,
+}
+
+impl FromStr for Move {
+    type Err = ParseError;
+
+    fn from_str(input_str: &str) -> Result<Self, ParseError> {
+        lazy_static! {
+            static ref INST_RE: Regex = Regex::new(r"^move (\d+) from (\d+) to (\d+)$").unwrap();
+        }
+
+        let cap = INST_RE.captures(input_str).ok_or_else(|| {
+            ParseError::new(
+                Day5::DAY,
+                input_str,
+                input_str,
+                "expected an instruction like 'move 1 from 2 to 3'",
+            )
+        })?;
+        let count = script::number(input_str, &cap, 1)?;
+        let from = script::number(input_str, &cap, 2)?;
+        let to = script::number(input_str, &cap, 3)?;
+        trace!(count, from, to, "parsed move");
+
+        Ok(Move { count, from, to })
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "move {} from {} to {}", self.count, self.from, self.to)
+    }
+}
+
+/// A statement in the crane language. Stacks are numbered from 1 as in the
+/// drawing. See `script` for the syntax.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Instruction {
+    Move(Move),
+    /// Exchanges the whole contents of two stacks
+    Swap {
+        a: usize,
+        b: usize,
+    },
+    /// Turns the top `count` crates of a stack upside down
+    Reverse {
+        count: usize,
+        stack: usize,
+    },
+    Repeat {
+        times: usize,
+        body: Vec<Instruction>,
+    },
+}
+
+impl From<Move> for Instruction {
+    fn from(m: Move) -> Instruction {
+        Instruction::Move(m)
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::Move(m) => write!(f, "{}", m),
+            Instruction::Swap { a, b } => write!(f, "swap {} {}", a, b),
+            Instruction::Reverse { count, stack } => write!(f, "reverse {} on {}", count, stack),
+            Instruction::Repeat { times, body } => {
+                writeln!(f, "repeat {} {{", times)?;
+                for instruction in body {
+                    for line in instruction.to_string().lines() {
+                        writeln!(f, "    {}", line)?;
+                    }
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+/// Deserializing checks the script against the same limits as parsing.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(try_from = "UncheckedProblem")]
+pub struct Problem {
+    pub stacks: Vec<Stack>,
+    pub instructions: Vec<Instruction>,
+}
+
+/// A deserialized `Problem` whose script hasn't been checked yet.
+#[derive(Deserialize)]
+struct UncheckedProblem {
+    stacks: Vec<Stack>,
+    instructions: Vec<Instruction>,
+}
+
+impl TryFrom<UncheckedProblem> for Problem {
+    type Error = String;
+
+    fn try_from(problem: UncheckedProblem) -> Result<Problem, String> {
+        script::check_script(&problem.instructions)?;
+        Ok(Problem {
+            stacks: problem.stacks,
+            instructions: problem.instructions,
+        })
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.render_stacks())?;
+        writeln!(f)?;
+        for instruction in &self.instructions {
+            writeln!(f, "{}", instruction)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Problem {
+    type Err = ParseError;
+
+    /// Parses any problem, even one whose instructions can't all run, so
+    /// they can be run leniently or stepped through up to the bad one.
+    fn from_str(input_str: &str) -> Result<Self, ParseError> {
+        Problem::parse_located(input_str).map(|(problem, _)| problem)
+    }
+}
+
+impl Problem {
+    /// Parses a problem, keeping where each instruction starts.
+    fn parse_located(input_str: &str) -> Result<(Problem, Vec<Location<'_>>), ParseError> {
+        let blocks = input::blocks(input_str);
+        if blocks.len() < 2 {
+            let (number, last_line) = input::lines(input_str).last().unwrap_or((1, ""));
+            return Err(ParseError::new(
+                Day5::DAY,
+                last_line,
+                &last_line[last_line.len()..],
+                "expected a blank line between the stack drawing and the instructions",
+            )
+            .at_line(number));
+        }
+
+        let stacks = drawing::parse_drawing_lines(&blocks[0])?;
+        let located = script::parse_located(&blocks[1..].concat())?;
+        let (instructions, locations): (Vec<Instruction>, Vec<Location>) =
+            located.into_iter().unzip();
+        debug!(
+            stacks = stacks.len(),
+            crates = stacks.iter().map(Vec::len).sum::<usize>(),
+            instructions = instructions.len(),
+            "parsed problem"
+        );
+
+        let problem = Problem {
+            stacks,
+            instructions,
+        };
+        Ok((problem, locations))
+    }
+
+    /// Reports `e`, from running the problem parsed from `input_str`, as
+    /// `Day5::parse` would, pointing at the line its instruction starts on.
+    pub fn locate_exec_error(input_str: &str, e: &ExecError) -> ParseError {
+        match Problem::parse_located(input_str) {
+            Ok((problem, locations)) => problem.exec_parse_error(&locations, e),
+            Err(parse_error) => parse_error,
+        }
+    }
+
+    fn exec_parse_error(&self, locations: &[Location], e: &ExecError) -> ParseError {
+        let message = match self.instructions.get(e.index()) {
+            Some(Instruction::Repeat { .. }) => {
+                format!("'{}' in this repeat: {}", e.instruction(), e.reason())
+            }
+            _ => e.reason(),
+        };
+        match locations.get(e.index()) {
+            Some(location) => location.error(message),
+            None => ParseError::new(Day5::DAY, "", "", message),
+        }
+    }
+
+    pub fn stacks_from_str(input_str: &str) -> Result<Vec<Stack>, ParseError> {
+        drawing::parse_drawing(input_str)
+    }
+
+    pub fn render_stacks(&self) -> String {
+        drawing::render_drawing(&self.stacks)
+    }
+
+    /// Like `execute`, but panics if an instruction can't be carried out.
+    #[cfg(test)]
+    pub(crate) fn solve(&self, crane: Crane) -> Problem {
+        self.execute(crane).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn top_crates(&self) -> String {
+        top_crates(&self.stacks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_parse_checks_instructions() {
+        let err = Day5::parse("[A]\n 1 \n\nmove 2 from 1 to 1\n").unwrap_err();
+        assert_eq!(err.message, "stack 1 only holds 1 crate, 1 short");
+        assert_eq!((err.line, err.column), (4, 1));
+
+        let input = "[A]\n 1 \n\nmove 1 from 1 to 1\nmove 1 from 1 to 2  # nowhere\n";
+        let err = Day5::parse(input).unwrap_err();
+        assert_eq!(
+            err.message,
+            "there is no stack 2, stacks are numbered 1 to 1"
+        );
+        assert_eq!((err.line, err.width), (5, 18));
+
+        let input = "[A]\n 1 \n\nrepeat 2 {\n    reverse 2 on 1\n}\n";
+        let err = Day5::parse(input).unwrap_err();
+        assert_eq!(
+            err.message,
+            "'reverse 2 on 1' in this repeat: stack 1 only holds 1 crate, 1 short"
+        );
+        assert_eq!(err.line, 4);
+
+        // Still readable without the check, for lenient runs
+        assert!(input.parse::<Problem>().is_ok());
+
+        let input = example().to_string();
+        let checked = Day5::parse(&input).unwrap();
+        assert_eq!(
+            Day5::part1(&checked),
+            checked.solve(Crane::CrateMover9000).top_crates()
+        );
+        assert_eq!(checked.into_problem(), example());
+    }
+
+    #[test]
+    fn test_locate_exec_error() {
+        let input = "[A]\n 1 \n\nmove 1 from 1 to 1\n\nswap 1 1\nmove 3 from 1 to 1\n";
+        let e = input
+            .parse::<Problem>()
+            .unwrap()
+            .execute(Crane::CrateMover9001)
+            .unwrap_err();
+        let err = Problem::locate_exec_error(input, &e);
+        assert_eq!(err, Day5::parse(input).unwrap_err());
+        assert_eq!((err.line, err.column, err.width), (7, 1, 18));
+        assert_eq!(err.message, "stack 1 only holds 1 crate, 2 short");
+    }
+
+    #[test]
+    fn test_instruction_from_str() {
+        let input_str = "move 1 from 2 to 1";
+        let actual = Instruction::from_str(input_str).unwrap();
+        let expected = Instruction::Move(Move {
+            count: 1,
+            from: 2,
+            to: 1,
+        });
+        assert_eq!(actual, expected);
+
+        let input_str = "move 3 from 1 to 3";
+        let actual = Instruction::from_str(input_str).unwrap();
+        let expected = Instruction::Move(Move {
+            count: 3,
+            from: 1,
+            to: 3,
+        });
+        assert_eq!(actual, expected);
+
+        let input_str = "move 2 from 2 to 1";
+        let actual = Instruction::from_str(input_str).unwrap();
+        let expected = Instruction::Move(Move {
+            count: 2,
+            from: 2,
+            to: 1,
+        });
+        assert_eq!(actual, expected);
+
+        let input_str = "move 1 from 1 to 2";
+        let actual = Instruction::from_str(input_str).unwrap();
+        let expected = Instruction::Move(Move {
+            count: 1,
+            from: 1,
+            to: 2,
+        });
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_construct_problem() {
+        let input_str = r#"    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 
+
+move 1 from 2 to 1"#;
+        println!("{}", input_str);
+        let expected = Problem {
+            stacks: stacks_of(&["ZN", "MCD", "P"]),
+            instructions: vec![Instruction::Move(Move {
+                count: 1,
+                from: 2,
+                to: 1,
+            })],
+        };
+
+        let actual = Problem::from_str(input_str).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_solve() {
+        let input_str = r#"    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2"#;
+        let problem = Problem::from_str(input_str).unwrap();
+
+        let solved = problem.solve(Crane::CrateMover9000);
+        assert_eq!(solved.top_crates(), "CMZ");
+
+        let solved = problem.solve(Crane::CrateMover9001);
+        assert_eq!(solved.top_crates(), "MCD");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = Instruction::from_str("move 1 from two to 3").unwrap_err();
+        assert_eq!((err.column, err.width), (1, 20));
+
+        let input_str = "[A]\n 1 \n\nmove 1 from 1 to 1\nmove 99999999999999999999 from 1 to 1";
+        let err = Problem::from_str(input_str).unwrap_err();
+        assert_eq!((err.line, err.column, err.width), (5, 6, 20));
+
+        let err = Problem::from_str("[A] [#]\n 1   2 \n\nmove 1 from 1 to 2").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 6));
+        assert_eq!(err.message, "'#' is not a valid crate label");
+
+        let err = Problem::from_str("[A]\n 1 \nmove 1 from 1 to 1").unwrap_err();
+        assert_eq!(err.line, 3);
+    }
+
+    #[test]
+    fn test_trimmed_stack_lines() {
+        let stacks = Problem::stacks_from_str("    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3").unwrap();
+        assert_eq!(stacks, stacks_of(&["ZN", "MCD", "P"]));
+    }
+
+    #[test]
+    fn test_windows_edited_input() {
+        let input_str = "    [D]    \r\n[N] [C]    \r\n[Z] [M] [P]\r\n 1   2   3 \r\n\r\nmove 1 from 2 to 1\r\nmove 3 from 1 to 3\r\nmove 2 from 2 to 1\r\nmove 1 from 1 to 2\r\n";
+        let problem = Problem::from_str(input_str).unwrap();
+        assert_eq!(problem.instructions.len(), 4);
+        assert_eq!(problem.solve(Crane::CrateMover9001).top_crates(), "MCD");
+    }
+
+    #[test]
+    fn test_editor_terminated_input() {
+        // Trailing whitespace stripped and a final newline added
+        let input_str = "    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3\n\nmove 1 from 2 to 1\n";
+        let problem = Problem::from_str(input_str).unwrap();
+        assert_eq!(problem.stacks, stacks_of(&["ZN", "MCD", "P"]));
+        assert_eq!(problem.instructions.len(), 1);
+    }
+
+    #[test]
+    fn test_problem_round_trip() {
+        let input_str = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n\nmove 1 from 2 to 1\nmove 3 from 1 to 3\n";
+        let problem = Problem::from_str(input_str).unwrap();
+        assert_eq!(problem.to_string(), input_str);
+
+        let problem = Problem::from_str(include_str!("input.txt")).unwrap();
+        assert_eq!(Problem::from_str(&problem.to_string()).unwrap(), problem);
+
+        let solved = problem.solve(Crane::CrateMover9000);
+        assert_eq!(Problem::from_str(&solved.to_string()).unwrap(), solved);
+    }
+}
@@ -0,0 +1,226 @@
+use super::{Crane, Crate, Instruction, Move, Problem, Stack};
+use std::collections::hash_map::{Entry, HashMap};
+use std::error::Error;
+use std::fmt;
+
+/// Upper bound on the configurations a search will hold before giving up.
+pub const MAX_STATES: usize = 1 << 20;
+
+/// Why `plan` couldn't produce a list of instructions.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum PlanError {
+    StackCount { start: usize, target: usize },
+    DifferentCrates,
+    NotFound { max_moves: usize },
+    TooManyStates { explored: usize },
+}
+
+impl fmt::Display for PlanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlanError::StackCount { start, target } => write!(
+                f,
+                "the start has {} stacks but the target has {}",
+                start, target
+            ),
+            PlanError::DifferentCrates => {
+                write!(f, "the start and target don't hold the same crates")
+            }
+            PlanError::NotFound { max_moves } => {
+                write!(f, "the target can't be reached in {} moves", max_moves)
+            }
+            PlanError::TooManyStates { explored } => {
+                write!(f, "gave up after exploring {} configurations", explored)
+            }
+        }
+    }
+}
+
+impl Error for PlanError {}
+
+/// Configurations found by the search, each with the move that reached it.
+/// The start has none.
+type Reached = HashMap<Vec<Stack>, Option<Move>>;
+
+/// Searches breadth first for a shortest list of at most `max_moves`
+/// instructions that takes `start` to `target` with `crane`. The plan comes
+/// back as a `Problem` starting from `start`, so it can be written out as a
+/// puzzle.
+pub fn plan(
+    start: &[Stack],
+    target: &[Stack],
+    crane: Crane,
+    max_moves: usize,
+) -> Result<Problem, PlanError> {
+    if start.len() != target.len() {
+        return Err(PlanError::StackCount {
+            start: start.len(),
+            target: target.len(),
+        });
+    }
+    if sorted_crates(start) != sorted_crates(target) {
+        return Err(PlanError::DifferentCrates);
+    }
+
+    // Each configuration is kept once, in the layer it was first reached in
+    let mut earlier = Reached::new();
+    let mut layer = Reached::from([(start.to_vec(), None)]);
+    let mut found = start == target;
+    for _ in 0..max_moves {
+        if found || layer.is_empty() {
+            break;
+        }
+        // Sorted so the same puzzle always gets the same plan
+        let mut currents = layer.keys().collect::<Vec<_>>();
+        currents.sort_unstable();
+        let mut next = Reached::new();
+        'layer: for current in currents {
+            for instruction in moves(current) {
+                let mut stacks = current.clone();
+                crane.move_crates(&mut stacks, &instruction);
+                if earlier.contains_key(&stacks) || layer.contains_key(&stacks) {
+                    continue;
+                }
+                let explored = earlier.len() + layer.len() + next.len();
+                let Entry::Vacant(entry) = next.entry(stacks) else {
+                    continue;
+                };
+                if explored == MAX_STATES {
+                    return Err(PlanError::TooManyStates { explored });
+                }
+                found = entry.key() == target;
+                entry.insert(Some(instruction));
+                if found {
+                    break 'layer;
+                }
+            }
+        }
+        earlier.extend(layer);
+        layer = next;
+    }
+
+    if !found {
+        return Err(PlanError::NotFound { max_moves });
+    }
+    // Moving the same crates straight back undoes a move with either crane
+    let mut stacks = target.to_vec();
+    let mut instructions = Vec::new();
+    while let Some(Some(instruction)) = layer.get(&stacks).or_else(|| earlier.get(&stacks)) {
+        let back = Move {
+            count: instruction.count,
+            from: instruction.to,
+            to: instruction.from,
+        };
+        instructions.push(Instruction::Move(instruction.clone()));
+        crane.move_crates(&mut stacks, &back);
+    }
+    instructions.reverse();
+
+    Ok(Problem {
+        stacks: start.to_vec(),
+        instructions,
+    })
+}
+
+fn sorted_crates(stacks: &[Stack]) -> Vec<&Crate> {
+    let mut crates = stacks.iter().flatten().collect::<Vec<_>>();
+    crates.sort();
+    crates
+}
+
+/// Every move that can run against `stacks`.
+fn moves(stacks: &[Stack]) -> impl Iterator<Item = Move> + '_ {
+    (0..stacks.len()).flat_map(move |from| {
+        (0..stacks.len())
+            .filter(move |&to| to != from)
+            .flat_map(move |to| {
+                (1..=stacks[from].len()).map(move |count| Move {
+                    count,
+                    from: from + 1,
+                    to: to + 1,
+                })
+            })
+    })
+}
+
+impl Problem {
+    /// Whether running the instructions with `crane` ends at `target`, for
+    /// checking hand-written solutions.
+    pub fn reaches(&self, target: &[Stack], crane: Crane) -> bool {
+        match self.execute(crane) {
+            Ok(solved) => solved.stacks == target,
+            Err(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day5::stacks_of;
+
+    #[test]
+    fn test_plan_example() {
+        let start = stacks_of(&["ZN", "MCD", "P"]);
+        let target = stacks_of(&["C", "M", "PDNZ"]);
+
+        let planned = plan(&start, &target, Crane::CrateMover9000, 4).unwrap();
+        assert!(planned.instructions.len() <= 4);
+        assert!(planned.reaches(&target, Crane::CrateMover9000));
+        assert_eq!(planned.stacks, start);
+
+        let reparsed = planned.to_string().parse::<Problem>().unwrap();
+        assert!(reparsed.reaches(&target, Crane::CrateMover9000));
+
+        for _ in 0..5 {
+            assert_eq!(
+                plan(&start, &target, Crane::CrateMover9000, 4),
+                Ok(planned.clone())
+            );
+        }
+    }
+
+    #[test]
+    fn test_plan_is_shortest() {
+        let start = stacks_of(&["ABC", ""]);
+
+        let planned = plan(&start, &start, Crane::CrateMover9000, 3).unwrap();
+        assert!(planned.instructions.is_empty());
+
+        let target = stacks_of(&["", "CBA"]);
+        let planned = plan(&start, &target, Crane::CrateMover9000, 3).unwrap();
+        assert_eq!(planned.instructions.len(), 1);
+        assert_eq!(planned.instructions[0].to_string(), "move 3 from 1 to 2");
+
+        let target = stacks_of(&["", "ABC"]);
+        let planned = plan(&start, &target, Crane::CrateMover9001, 3).unwrap();
+        assert_eq!(planned.instructions.len(), 1);
+    }
+
+    #[test]
+    fn test_plan_errors() {
+        let start = stacks_of(&["AB", ""]);
+
+        let err = plan(&start, &stacks_of(&["AB"]), Crane::CrateMover9000, 3).unwrap_err();
+        assert_eq!(
+            err,
+            PlanError::StackCount {
+                start: 2,
+                target: 1
+            }
+        );
+
+        let err = plan(&start, &stacks_of(&["AC", ""]), Crane::CrateMover9000, 3).unwrap_err();
+        assert_eq!(err, PlanError::DifferentCrates);
+
+        // Swapping two crates needs a third stack to hold one of them
+        let err = plan(&start, &stacks_of(&["BA", ""]), Crane::CrateMover9000, 5).unwrap_err();
+        assert_eq!(err, PlanError::NotFound { max_moves: 5 });
+
+        let start = stacks_of(&["AB", "", ""]);
+        let target = stacks_of(&["BA", "", ""]);
+        let planned = plan(&start, &target, Crane::CrateMover9000, 5).unwrap();
+        assert!(planned.reaches(&target, Crane::CrateMover9000));
+        assert!(plan(&start, &target, Crane::CrateMover9000, 1).is_err());
+    }
+}
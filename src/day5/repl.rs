@@ -0,0 +1,263 @@
+use super::{
+    parse_drawing, render_drawing, top_crates, Crane, History, Instruction, Problem, Stack,
+};
+use crate::input;
+use std::fs;
+
+pub const HELP: &str = "Type an instruction to run it, e.g. 'move 2 from 1 to 3', or one of
+  show          print the stacks
+  top           print the crate on top of each stack
+  undo, redo    take back or put back the last instruction
+  history       list the instructions run so far
+  save <path>   write the starting drawing and instructions run so far
+  load <path>   start again from a drawing or puzzle file
+  help, quit";
+
+/// What the caller should do after a line of input.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Reply {
+    /// Print this and read another line
+    Print(String),
+    /// A `repeat` block is open; read another line for it
+    More,
+    Quit,
+}
+
+/// An interactive crane session. Lines go in through `handle`; doing the
+/// reading and printing is up to the caller.
+pub struct Repl {
+    start: Vec<Stack>,
+    history: History,
+    crane: Crane,
+    /// Lines of a `repeat` block still being typed
+    pending: Vec<String>,
+}
+
+impl Repl {
+    pub fn new(stacks: Vec<Stack>, crane: Crane) -> Repl {
+        Repl {
+            start: stacks.clone(),
+            history: History::new(stacks, crane),
+            crane,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Starts from a drawing on its own, or from a whole puzzle with its
+    /// instructions already run so they can be undone.
+    /// Errors come back ready to print.
+    pub fn parse(input_str: &str, crane: Crane) -> Result<Repl, String> {
+        if input::blocks(input_str).len() < 2 {
+            let stacks = parse_drawing(input_str).map_err(|e| e.render())?;
+            return Ok(Repl::new(stacks, crane));
+        }
+
+        let problem = input_str.parse::<Problem>().map_err(|e| e.render())?;
+        let mut repl = Repl::new(problem.stacks, crane);
+        for instruction in problem.instructions {
+            repl.history
+                .apply(instruction)
+                .map_err(|e| format!("error: {}", e))?;
+        }
+        Ok(repl)
+    }
+
+    pub fn stacks(&self) -> &[Stack] {
+        self.history.stacks()
+    }
+
+    pub fn prompt(&self) -> &'static str {
+        match self.pending.is_empty() {
+            true => "> ",
+            false => "... ",
+        }
+    }
+
+    /// The starting drawing and the instructions run so far.
+    pub fn session(&self) -> Problem {
+        Problem {
+            stacks: self.start.clone(),
+            instructions: self.history.applied().cloned().collect(),
+        }
+    }
+
+    pub fn handle(&mut self, line: &str) -> Reply {
+        if !self.pending.is_empty() || line.trim_start().starts_with("repeat") {
+            return self.continue_block(line);
+        }
+
+        let line = line.trim();
+        let (command, arg) = match line.split_once(' ') {
+            Some((command, arg)) => (command, arg.trim()),
+            None => (line, ""),
+        };
+        match (command, arg) {
+            ("", _) => Reply::Print(String::new()),
+            ("show", "") => Reply::Print(render_drawing(self.stacks())),
+            ("top", "") => Reply::Print(top_crates(self.stacks())),
+            ("undo", "") => match self.history.undo().map(|i| format!("undid {}", i)) {
+                Some(note) => self.show(&note),
+                None => Reply::Print("nothing to undo".to_owned()),
+            },
+            ("redo", "") => match self.history.redo().map(|i| format!("redid {}", i)) {
+                Some(note) => self.show(&note),
+                None => Reply::Print("nothing to redo".to_owned()),
+            },
+            ("history", "") => Reply::Print(
+                self.history
+                    .applied()
+                    .map(Instruction::to_string)
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+            ("save", path) if !path.is_empty() => match fs::write(path, self.session().to_string())
+            {
+                Ok(()) => Reply::Print(format!("saved to {}", path)),
+                Err(e) => Reply::Print(format!("error: can't write {}: {}", path, e)),
+            },
+            ("load", path) if !path.is_empty() => self.load(path),
+            ("help", "") => Reply::Print(HELP.to_owned()),
+            ("quit" | "exit", "") => Reply::Quit,
+            _ => match line.parse::<Instruction>() {
+                Ok(instruction) => self.run(instruction),
+                Err(e) => Reply::Print(e.render()),
+            },
+        }
+    }
+
+    /// Collects the lines of a `repeat` block, running it once it's closed.
+    fn continue_block(&mut self, line: &str) -> Reply {
+        self.pending.push(line.to_owned());
+        let text = self.pending.join("\n");
+        let opened = text.matches('{').count();
+        if opened > text.matches('}').count() {
+            return Reply::More;
+        }
+
+        self.pending.clear();
+        match text.parse::<Instruction>() {
+            Ok(instruction) => self.run(instruction),
+            Err(e) => Reply::Print(e.render()),
+        }
+    }
+
+    fn run(&mut self, instruction: Instruction) -> Reply {
+        match self.history.apply(instruction) {
+            Ok(()) => self.show(""),
+            Err(e) => Reply::Print(format!("error: {}", e)),
+        }
+    }
+
+    fn load(&mut self, path: &str) -> Reply {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) => return Reply::Print(format!("error: can't read {}: {}", path, e)),
+        };
+        match Repl::parse(&text, self.crane) {
+            Ok(repl) => {
+                *self = repl;
+                self.show(&format!("loaded {}", path))
+            }
+            Err(e) => Reply::Print(e),
+        }
+    }
+
+    /// The drawing, after a note if there is one.
+    fn show(&self, note: &str) -> Reply {
+        let drawing = render_drawing(self.stacks());
+        match note {
+            "" => Reply::Print(drawing),
+            note => Reply::Print(format!("{}\n{}", note, drawing)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day5::stacks_of;
+    use std::env;
+
+    const DRAWING: &str = "    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3";
+
+    fn printed(reply: Reply) -> String {
+        match reply {
+            Reply::Print(text) => text,
+            other => panic!("expected output, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_session() {
+        let mut repl = Repl::parse(DRAWING, Crane::CrateMover9000).unwrap();
+        assert_eq!(printed(repl.handle("top")), "NDP");
+
+        let shown = printed(repl.handle("move 1 from 2 to 1"));
+        assert_eq!(shown, render_drawing(&stacks_of(&["ZND", "MC", "P"])));
+        repl.handle("move 3 from 1 to 3");
+        assert_eq!(printed(repl.handle("top")), "CZ");
+
+        assert!(printed(repl.handle("undo")).starts_with("undid move 3 from 1 to 3\n"));
+        assert_eq!(printed(repl.handle("top")), "DCP");
+        repl.handle("redo");
+        assert_eq!(
+            printed(repl.handle("history")),
+            "move 1 from 2 to 1\nmove 3 from 1 to 3"
+        );
+
+        assert_eq!(repl.handle("quit"), Reply::Quit);
+    }
+
+    #[test]
+    fn test_errors_leave_state() {
+        let mut repl = Repl::parse(DRAWING, Crane::CrateMover9000).unwrap();
+
+        let reply = printed(repl.handle("move 5 from 3 to 1"));
+        assert!(reply.starts_with("error: instruction 1"));
+        let reply = printed(repl.handle("lift 1"));
+        assert!(reply.contains("unknown instruction 'lift'"));
+        assert_eq!(printed(repl.handle("undo")), "nothing to undo");
+        assert_eq!(repl.stacks(), stacks_of(&["ZN", "MCD", "P"]).as_slice());
+    }
+
+    #[test]
+    fn test_repeat_block() {
+        let mut repl = Repl::parse(DRAWING, Crane::CrateMover9001).unwrap();
+        assert_eq!(repl.handle("repeat 2 {"), Reply::More);
+        assert_eq!(repl.prompt(), "... ");
+        assert_eq!(repl.handle("    move 1 from 1 to 3"), Reply::More);
+        printed(repl.handle("}"));
+        assert_eq!(repl.prompt(), "> ");
+        assert_eq!(repl.stacks(), stacks_of(&["", "MCD", "PNZ"]).as_slice());
+
+        repl.handle("undo");
+        assert_eq!(repl.stacks(), stacks_of(&["ZN", "MCD", "P"]).as_slice());
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = env::temp_dir().join(format!("day5-repl-{}.txt", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        let mut repl = Repl::parse(DRAWING, Crane::CrateMover9000).unwrap();
+        repl.handle("move 1 from 2 to 1");
+        repl.handle("swap 1 3");
+        assert_eq!(
+            printed(repl.handle(&format!("save {}", path))),
+            format!("saved to {}", path)
+        );
+
+        let mut loaded = Repl::parse(DRAWING, Crane::CrateMover9000).unwrap();
+        loaded.handle(&format!("load {}", path));
+        assert_eq!(loaded.stacks(), repl.stacks());
+        assert_eq!(loaded.session(), repl.session());
+        loaded.handle("undo");
+        loaded.handle("undo");
+        assert_eq!(loaded.stacks(), stacks_of(&["ZN", "MCD", "P"]).as_slice());
+
+        fs::remove_file(path).unwrap();
+    }
+}
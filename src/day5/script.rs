@@ -0,0 +1,348 @@
+/*
+The crane language, one statement per line
+
+    # comments run to the end of a line
+    move 1 from 2 to 1
+    swap 1 3            # exchange two whole stacks
+    reverse 2 on 1      # turn the top two crates of stack 1 upside down
+    repeat 3 {
+        move 1 from 1 to 2
+    }
+*/
+
+use super::{Day5, Instruction};
+use crate::input;
+use crate::{ParseError, Solution};
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use std::str::FromStr;
+
+/// Most statements a script may expand to once its `repeat` blocks are
+/// unrolled, so a typo in a repeat count can't hang a run.
+pub const MAX_STATEMENTS: usize = 10_000_000;
+
+impl Instruction {
+    /// Number of statements this instruction carries out, counting each run
+    /// of a `repeat` body. Saturates rather than overflowing.
+    pub fn statement_count(&self) -> usize {
+        match self {
+            Instruction::Repeat { times, body } => body
+                .iter()
+                .fold(0usize, |n, instruction| {
+                    n.saturating_add(instruction.statement_count())
+                })
+                .saturating_mul(*times),
+            _ => 1,
+        }
+    }
+}
+
+/// Holds instructions that didn't come through the parser, such as
+/// deserialized ones, to the parser's limits: no empty `repeat` bodies and no
+/// more than `MAX_STATEMENTS` statements once unrolled.
+pub(super) fn check_script(instructions: &[Instruction]) -> Result<(), String> {
+    fn has_empty_repeat(instruction: &Instruction) -> bool {
+        match instruction {
+            Instruction::Repeat { body, .. } => {
+                body.is_empty() || body.iter().any(has_empty_repeat)
+            }
+            _ => false,
+        }
+    }
+
+    if instructions.iter().any(has_empty_repeat) {
+        return Err("a 'repeat' has nothing to repeat".to_string());
+    }
+    let statements = instructions.iter().fold(0usize, |n, instruction| {
+        n.saturating_add(instruction.statement_count())
+    });
+    if statements > MAX_STATEMENTS {
+        return Err(format!(
+            "the script runs past {} statements once repeats are unrolled",
+            MAX_STATEMENTS
+        ));
+    }
+    Ok(())
+}
+
+impl FromStr for Instruction {
+    type Err = ParseError;
+
+    /// Parses a single statement, which may be a multi-line `repeat` block.
+    fn from_str(input_str: &str) -> Result<Self, ParseError> {
+        let lines = input::lines(input_str).collect::<Vec<_>>();
+        let mut instructions = parse_instructions(&lines)?;
+        if instructions.len() != 1 {
+            let message = format!("expected one instruction, found {}", instructions.len());
+            return Err(ParseError::new(Day5::DAY, input_str, input_str, message));
+        }
+        Ok(instructions.remove(0))
+    }
+}
+
+/// Where an instruction starts: its line number, the whole line and the
+/// statement text on it, without any comment.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub(super) struct Location<'a> {
+    pub number: usize,
+    pub line: &'a str,
+    pub text: &'a str,
+}
+
+impl Location<'_> {
+    pub fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError::new(Day5::DAY, self.line, self.text, message).at_line(self.number)
+    }
+}
+
+/// Parses the instruction lines of a problem, skipping blank lines and comments.
+pub fn parse_instructions(lines: &[(usize, &str)]) -> Result<Vec<Instruction>, ParseError> {
+    Ok(parse_located(lines)?.into_iter().map(|(i, _)| i).collect())
+}
+
+/// Like `parse_instructions`, keeping where each instruction starts.
+pub(super) fn parse_located<'a>(
+    lines: &[(usize, &'a str)],
+) -> Result<Vec<(Instruction, Location<'a>)>, ParseError> {
+    parse_block(&mut lines.iter().copied(), None)
+}
+
+/// Parses statements up to the `}` closing the `repeat` on `opened_by`, or to
+/// the end of the input at the top level. Each block may expand to at most
+/// `MAX_STATEMENTS` statements.
+fn parse_block<'a>(
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+    opened_by: Option<(usize, &'a str)>,
+) -> Result<Vec<(Instruction, Location<'a>)>, ParseError> {
+    lazy_static! {
+        static ref REPEAT_RE: Regex = Regex::new(r"^repeat (\d+) \{$").unwrap();
+    }
+
+    let mut out = Vec::new();
+    let mut statements = 0usize;
+    while let Some((number, line)) = lines.next() {
+        let text = line.split('#').next().unwrap().trim();
+        if text.is_empty() {
+            continue;
+        }
+
+        if text == "}" {
+            if opened_by.is_none() {
+                return Err(
+                    ParseError::new(Day5::DAY, line, text, "'}' without a 'repeat'")
+                        .at_line(number),
+                );
+            }
+            return Ok(out);
+        }
+
+        let instruction = match REPEAT_RE.captures(text) {
+            Some(cap) => {
+                let times = number_at(line, text, &cap, 1).map_err(|e| e.at_line(number))?;
+                let body = parse_block(lines, Some((number, line)))?
+                    .into_iter()
+                    .map(|(i, _)| i)
+                    .collect::<Vec<Instruction>>();
+                if body.is_empty() {
+                    return Err(ParseError::new(
+                        Day5::DAY,
+                        line,
+                        text,
+                        "this 'repeat' has nothing to repeat",
+                    )
+                    .at_line(number));
+                }
+                Instruction::Repeat { times, body }
+            }
+            None => parse_statement(text).map_err(|e| e.in_line(line, text).at_line(number))?,
+        };
+
+        statements = statements.saturating_add(instruction.statement_count());
+        if statements > MAX_STATEMENTS {
+            let message = format!(
+                "this takes the script past {} statements once repeats are unrolled",
+                MAX_STATEMENTS
+            );
+            return Err(ParseError::new(Day5::DAY, line, text, message).at_line(number));
+        }
+        out.push((instruction, Location { number, line, text }));
+    }
+
+    match opened_by {
+        Some((number, line)) => Err(ParseError::new(
+            Day5::DAY,
+            line,
+            line.trim(),
+            "this 'repeat' is missing its closing '}'",
+        )
+        .at_line(number)),
+        None => Ok(out),
+    }
+}
+
+fn parse_statement(text: &str) -> Result<Instruction, ParseError> {
+    lazy_static! {
+        static ref SWAP_RE: Regex = Regex::new(r"^swap (\d+) (\d+)$").unwrap();
+        static ref REVERSE_RE: Regex = Regex::new(r"^reverse (\d+) on (\d+)$").unwrap();
+    }
+
+    let expected = |example: &str| {
+        ParseError::new(
+            Day5::DAY,
+            text,
+            text,
+            format!("expected an instruction like '{}'", example),
+        )
+    };
+
+    let verb = text.split(' ').next().unwrap();
+    match verb {
+        "move" => Ok(Instruction::Move(text.parse()?)),
+        "swap" => {
+            let cap = SWAP_RE.captures(text).ok_or_else(|| expected("swap 1 2"))?;
+            Ok(Instruction::Swap {
+                a: number(text, &cap, 1)?,
+                b: number(text, &cap, 2)?,
+            })
+        }
+        "reverse" => {
+            let cap = REVERSE_RE
+                .captures(text)
+                .ok_or_else(|| expected("reverse 2 on 1"))?;
+            Ok(Instruction::Reverse {
+                count: number(text, &cap, 1)?,
+                stack: number(text, &cap, 2)?,
+            })
+        }
+        "repeat" => Err(expected("repeat 2 {")),
+        _ => Err(ParseError::new(
+            Day5::DAY,
+            text,
+            verb,
+            format!("unknown instruction '{}'", verb),
+        )),
+    }
+}
+
+/// Reads capture group `i` of `cap`, matched against `text`, as a number.
+pub(super) fn number(text: &str, cap: &Captures, i: usize) -> Result<usize, ParseError> {
+    let m = cap.get(i).unwrap();
+    let digits = &text[m.range()];
+    digits
+        .parse::<usize>()
+        .map_err(|_| ParseError::new(Day5::DAY, text, digits, format!("{} is too large", digits)))
+}
+
+/// Like `number`, for a match against `text` that sits inside `line`.
+fn number_at(line: &str, text: &str, cap: &Captures, i: usize) -> Result<usize, ParseError> {
+    number(text, cap, i).map_err(|e| e.in_line(line, text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day5::{stacks_of, Crane, Move, Problem};
+
+    const SCRIPT: &str = "    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3
+
+# Put everything on stack 3
+repeat 2 {
+    move 1 from 2 to 1  # one at a time
+}
+move 4 from 1 to 3
+swap 2 3
+reverse 2 on 2
+";
+
+    #[test]
+    fn test_parse_script() {
+        let problem = Problem::from_str(SCRIPT).unwrap();
+        assert_eq!(
+            problem.instructions,
+            vec![
+                Instruction::Repeat {
+                    times: 2,
+                    body: vec![Instruction::Move(Move {
+                        count: 1,
+                        from: 2,
+                        to: 1
+                    })],
+                },
+                Instruction::Move(Move {
+                    count: 4,
+                    from: 1,
+                    to: 3
+                }),
+                Instruction::Swap { a: 2, b: 3 },
+                Instruction::Reverse { count: 2, stack: 2 },
+            ]
+        );
+
+        let solved = problem.execute(Crane::CrateMover9000).unwrap();
+        assert_eq!(solved.stacks, stacks_of(&["", "PCDZN", "M"]));
+    }
+
+    #[test]
+    fn test_script_round_trip() {
+        let problem = Problem::from_str(SCRIPT).unwrap();
+        let rendered = problem.to_string();
+        assert!(rendered.contains("repeat 2 {\n    move 1 from 2 to 1\n}\n"));
+        assert_eq!(Problem::from_str(&rendered).unwrap(), problem);
+
+        let nested = "repeat 2 {\n    repeat 3 {\n        swap 1 2\n    }\n}";
+        assert_eq!(Instruction::from_str(nested).unwrap().to_string(), nested);
+    }
+
+    #[test]
+    fn test_script_errors() {
+        let lines = |text: &'static str| input::lines(text).collect::<Vec<_>>();
+
+        let err = parse_instructions(&lines("swap 1\nmove 1 from 1 to 2")).unwrap_err();
+        assert_eq!(err.message, "expected an instruction like 'swap 1 2'");
+        assert_eq!(err.line, 1);
+
+        let err = parse_instructions(&lines("repeat 2 {\n  lift 1\n}")).unwrap_err();
+        assert_eq!(err.message, "unknown instruction 'lift'");
+        assert_eq!((err.line, err.column, err.width), (2, 3, 4));
+
+        let err = parse_instructions(&lines("repeat 2 {\n  move 1 from 1 to 2")).unwrap_err();
+        assert_eq!(err.message, "this 'repeat' is missing its closing '}'");
+        assert_eq!(err.line, 1);
+
+        let err = parse_instructions(&lines("move 1 from 1 to 2\n}")).unwrap_err();
+        assert_eq!(err.line, 2);
+
+        let err = Instruction::from_str("swap 1 2\nswap 2 1").unwrap_err();
+        assert_eq!(err.message, "expected one instruction, found 2");
+    }
+
+    #[test]
+    fn test_repeat_limits() {
+        let lines = |text: &'static str| input::lines(text).collect::<Vec<_>>();
+
+        let err = parse_instructions(&lines("swap 1 2\nrepeat 99999999999999 {\n}")).unwrap_err();
+        assert_eq!(err.message, "this 'repeat' has nothing to repeat");
+        assert_eq!(err.line, 2);
+
+        let err = parse_instructions(&lines(
+            "repeat 100 {\n    repeat 99999999999999 {\n        swap 1 2\n    }\n}",
+        ))
+        .unwrap_err();
+        assert!(err.message.contains("past 10000000 statements"));
+        assert_eq!(err.line, 2);
+
+        let err = parse_instructions(&lines(
+            "repeat 6000000 {\n    swap 1 2\n}\nrepeat 6000000 {\n    swap 1 2\n}",
+        ))
+        .unwrap_err();
+        assert_eq!(err.line, 4);
+
+        let nested = Instruction::from_str(
+            "repeat 3 {\n    swap 1 2\n    repeat 2 {\n        swap 2 1\n    }\n}",
+        );
+        assert_eq!(nested.unwrap().statement_count(), 9);
+    }
+}
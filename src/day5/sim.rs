@@ -0,0 +1,211 @@
+use super::{render_drawing, Crane, ExecError, Instruction, Problem, Stack};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The stacks after `step` instructions have run; step 0 is the starting drawing.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub step: usize,
+    /// The instruction that produced this state, if any
+    pub instruction: Option<Instruction>,
+    pub stacks: Vec<Stack>,
+}
+
+impl Snapshot {
+    pub fn render(&self) -> String {
+        render_drawing(&self.stacks)
+    }
+}
+
+impl fmt::Display for Snapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.instruction {
+            Some(instruction) => writeln!(f, "Step {}: {}", self.step, instruction)?,
+            None => writeln!(f, "Step {}", self.step)?,
+        }
+        write!(f, "{}", self.render())
+    }
+}
+
+/// Runs a problem's instructions one at a time. Iterating yields a snapshot
+/// after each instruction and stops after the last one or the first error.
+pub struct Simulation<'a> {
+    problem: &'a Problem,
+    crane: Crane,
+    stacks: Vec<Stack>,
+    step: usize,
+    failed: bool,
+}
+
+impl<'a> Simulation<'a> {
+    pub fn new(problem: &'a Problem, crane: Crane) -> Simulation<'a> {
+        Simulation {
+            problem,
+            crane,
+            stacks: problem.stacks.clone(),
+            step: 0,
+            failed: false,
+        }
+    }
+
+    /// Number of instructions applied so far.
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    pub fn stacks(&self) -> &[Stack] {
+        &self.stacks
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.failed || self.step == self.problem.instructions.len()
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            step: self.step,
+            instruction: self
+                .step
+                .checked_sub(1)
+                .map(|i| self.problem.instructions[i].clone()),
+            stacks: self.stacks.clone(),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.stacks = self.problem.stacks.clone();
+        self.step = 0;
+        self.failed = false;
+    }
+
+    /// Applies the next instruction, or returns `None` once finished.
+    pub fn advance(&mut self) -> Option<Result<&Instruction, ExecError>> {
+        if self.is_finished() {
+            return None;
+        }
+
+        let instruction = &self.problem.instructions[self.step];
+        if let Err(e) = instruction.apply(self.step, self.crane, &mut self.stacks) {
+            self.failed = true;
+            return Some(Err(e));
+        }
+        self.step += 1;
+        Some(Ok(instruction))
+    }
+
+    /// Moves to the state after `step` instructions, replaying from the start
+    /// when seeking backwards. Steps past the end stop at the last instruction.
+    pub fn seek(&mut self, step: usize) -> Result<(), ExecError> {
+        if step < self.step || self.failed {
+            self.reset();
+        }
+        while self.step < step {
+            match self.advance() {
+                Some(result) => result.map(|_| ())?,
+                None => break,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Iterator for Simulation<'_> {
+    type Item = Result<Snapshot, ExecError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.advance()?.map(|_| ());
+        Some(result.map(|_| self.snapshot()))
+    }
+}
+
+impl Problem {
+    pub fn simulate(&self, crane: Crane) -> Simulation<'_> {
+        Simulation::new(self, crane)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day5::{example, stacks_of};
+
+    #[test]
+    fn test_iterate() {
+        let problem = example();
+        let snapshots = problem
+            .simulate(Crane::CrateMover9000)
+            .collect::<Result<Vec<Snapshot>, ExecError>>()
+            .unwrap();
+
+        assert_eq!(snapshots.len(), 4);
+        assert_eq!(snapshots[0].step, 1);
+        assert_eq!(snapshots[0].stacks, stacks_of(&["ZND", "MC", "P"]));
+        assert_eq!(snapshots[1].stacks, stacks_of(&["", "MC", "PDNZ"]));
+        assert_eq!(
+            snapshots[3].stacks,
+            problem.solve(Crane::CrateMover9000).stacks
+        );
+    }
+
+    #[test]
+    fn test_seek() {
+        let problem = example();
+        let mut sim = problem.simulate(Crane::CrateMover9001);
+
+        sim.seek(3).unwrap();
+        assert_eq!(sim.step(), 3);
+        let forward = sim.snapshot();
+
+        sim.seek(1).unwrap();
+        assert_eq!(sim.step(), 1);
+        assert_eq!(sim.stacks(), stacks_of(&["ZND", "MC", "P"]));
+
+        sim.seek(3).unwrap();
+        assert_eq!(sim.snapshot(), forward);
+
+        sim.seek(100).unwrap();
+        assert!(sim.is_finished());
+        assert_eq!(sim.step(), 4);
+
+        sim.seek(0).unwrap();
+        assert_eq!(sim.stacks(), problem.stacks.as_slice());
+    }
+
+    #[test]
+    fn test_snapshot_render() {
+        let problem = example();
+        let mut sim = problem.simulate(Crane::CrateMover9000);
+        sim.seek(2).unwrap();
+
+        let expected = "Step 2: move 3 from 1 to 3
+        [Z]
+        [N]
+    [C] [D]
+    [M] [P]
+ 1   2   3 ";
+        assert_eq!(sim.snapshot().to_string(), expected);
+    }
+
+    #[test]
+    fn test_stops_on_error() {
+        let mut problem = example();
+        problem.instructions[1] = "move 10 from 1 to 3".parse().unwrap();
+
+        let results = problem.simulate(Crane::CrateMover9000).collect::<Vec<_>>();
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_ok());
+        assert_eq!(results[1].as_ref().unwrap_err().index(), 1);
+
+        let mut sim = problem.simulate(Crane::CrateMover9000);
+        assert!(sim.seek(3).is_err());
+        assert_eq!(sim.step(), 1);
+
+        // A repeat that fails partway doesn't leave half its moves behind
+        problem.instructions[1] = "repeat 5 {\n    move 1 from 1 to 3\n}".parse().unwrap();
+        let mut sim = problem.simulate(Crane::CrateMover9000);
+        sim.advance();
+        let before = sim.snapshot();
+        assert!(sim.advance().unwrap().is_err());
+        assert_eq!(sim.snapshot(), before);
+    }
+}
@@ -0,0 +1,239 @@
+use super::{Crane, ExecError, Instruction, Move, Problem, Stack};
+use std::fmt;
+
+/// What a run costs: `per_move` for every statement carried out, plus
+/// `per_crate` for every crate it handles. Priced as a `u128`, which holds
+/// any one statement's cost at the largest prices.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct CostModel {
+    pub per_move: u64,
+    pub per_crate: u64,
+}
+
+impl Default for CostModel {
+    fn default() -> CostModel {
+        CostModel {
+            per_move: 1,
+            per_crate: 1,
+        }
+    }
+}
+
+impl CostModel {
+    pub fn cost(&self, crates: usize) -> u128 {
+        u128::from(self.per_move) + u128::from(self.per_crate) * crates as u128
+    }
+}
+
+/// Crates lifted off and put onto one stack over a run.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Traffic {
+    pub taken: usize,
+    pub added: usize,
+}
+
+/// Totals for a run. A move handles the crates it carries, a swap every
+/// crate on both stacks and a reversal the crates it turns over, which are
+/// counted as taken off and put back on their stack. Statements in a
+/// `repeat` count each time they run.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RunStats {
+    pub statements: usize,
+    pub crates_moved: usize,
+    /// Tallest each stack got, including its starting height
+    pub max_heights: Vec<usize>,
+    pub traffic: Vec<Traffic>,
+    /// Summed as a `u128`, so no run that finishes can overflow it
+    pub cost: u128,
+}
+
+impl RunStats {
+    fn new(stacks: &[Stack]) -> RunStats {
+        RunStats {
+            statements: 0,
+            crates_moved: 0,
+            max_heights: stacks.iter().map(Vec::len).collect(),
+            traffic: vec![Traffic::default(); stacks.len()],
+            cost: 0,
+        }
+    }
+
+    /// Counts a validated statement other than `repeat`, before it runs.
+    fn count(&mut self, instruction: &Instruction, stacks: &[Stack], costs: &CostModel) {
+        let crates = match *instruction {
+            Instruction::Move(Move { count, from, to }) => {
+                self.traffic[from - 1].taken += count;
+                self.traffic[to - 1].added += count;
+                count
+            }
+            Instruction::Swap { a, b } if a == b => 0,
+            Instruction::Swap { a, b } => {
+                let (len_a, len_b) = (stacks[a - 1].len(), stacks[b - 1].len());
+                self.traffic[a - 1].taken += len_a;
+                self.traffic[a - 1].added += len_b;
+                self.traffic[b - 1].taken += len_b;
+                self.traffic[b - 1].added += len_a;
+                len_a + len_b
+            }
+            Instruction::Reverse { count, stack } => {
+                self.traffic[stack - 1].taken += count;
+                self.traffic[stack - 1].added += count;
+                count
+            }
+            Instruction::Repeat { .. } => unreachable!("repeat is expanded by for_each_statement"),
+        };
+        self.statements += 1;
+        self.crates_moved += crates;
+        self.cost += costs.cost(crates);
+    }
+
+    /// Takes in the heights after a statement has run.
+    fn observe(&mut self, stacks: &[Stack]) {
+        for (max, stack) in self.max_heights.iter_mut().zip(stacks) {
+            *max = (*max).max(stack.len());
+        }
+    }
+}
+
+impl fmt::Display for RunStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} crates moved by {} statements, cost {}",
+            self.crates_moved, self.statements, self.cost
+        )?;
+        write!(f, "stack  max height  taken  added")?;
+        for (i, (max, traffic)) in self.max_heights.iter().zip(&self.traffic).enumerate() {
+            write!(
+                f,
+                "\n{:>5}  {:>10}  {:>5}  {:>5}",
+                i + 1,
+                max,
+                traffic.taken,
+                traffic.added
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Instruction {
+    /// Like `apply`, counting each statement into `stats`.
+    fn apply_counted(
+        &self,
+        index: usize,
+        crane: Crane,
+        stacks: &mut [Stack],
+        stats: &mut RunStats,
+        costs: &CostModel,
+    ) -> Result<(), ExecError> {
+        self.for_each_statement(&mut |statement| {
+            statement.validate(index, stacks)?;
+            stats.count(statement, stacks, costs);
+            statement.run(crane, stacks);
+            stats.observe(stacks);
+            Ok(())
+        })
+    }
+}
+
+impl Problem {
+    /// Like `execute`, also returning statistics for the run priced with
+    /// `costs`.
+    pub fn execute_with_stats(
+        &self,
+        crane: Crane,
+        costs: &CostModel,
+    ) -> Result<(Problem, RunStats), ExecError> {
+        let mut out = self.clone();
+        let mut stats = RunStats::new(&self.stacks);
+        for (index, instruction) in self.instructions.iter().enumerate() {
+            instruction.apply_counted(index, crane, &mut out.stacks, &mut stats, costs)?;
+        }
+        Ok((out, stats))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day5::{example, problem};
+
+    #[test]
+    fn test_example_stats() {
+        let problem = example();
+        for crane in [Crane::CrateMover9000, Crane::CrateMover9001] {
+            let (solved, stats) = problem
+                .execute_with_stats(crane, &CostModel::default())
+                .unwrap();
+            assert_eq!(solved, problem.solve(crane));
+            assert_eq!(stats.statements, 4);
+            assert_eq!(stats.crates_moved, 7);
+            assert_eq!(stats.max_heights, vec![3, 3, 4]);
+            assert_eq!(
+                stats.traffic,
+                vec![
+                    Traffic { taken: 4, added: 3 },
+                    Traffic { taken: 3, added: 1 },
+                    Traffic { taken: 0, added: 3 },
+                ]
+            );
+            assert_eq!(stats.cost, 11);
+        }
+    }
+
+    #[test]
+    fn test_costs_compare_scripts() {
+        // Both end with the same tops, one crate at a time or all at once
+        let one_by_one = problem("repeat 3 {\n    move 1 from 2 to 3\n}");
+        let chunk = problem("move 3 from 2 to 3\nreverse 3 on 3");
+        let crane = Crane::CrateMover9001;
+        assert_eq!(
+            one_by_one.solve(crane).top_crates(),
+            chunk.solve(crane).top_crates()
+        );
+
+        let costs = CostModel {
+            per_move: 10,
+            per_crate: 1,
+        };
+        let (_, slow) = one_by_one.execute_with_stats(crane, &costs).unwrap();
+        let (_, fast) = chunk.execute_with_stats(crane, &costs).unwrap();
+        assert_eq!((slow.statements, slow.crates_moved, slow.cost), (3, 3, 33));
+        assert_eq!((fast.statements, fast.crates_moved, fast.cost), (2, 6, 26));
+        assert_eq!(fast.traffic[2], Traffic { taken: 3, added: 6 });
+    }
+
+    #[test]
+    fn test_swap_traffic_and_errors() {
+        let (_, stats) = problem("swap 1 2\nswap 3 3")
+            .execute_with_stats(Crane::CrateMover9000, &CostModel::default())
+            .unwrap();
+        assert_eq!(stats.crates_moved, 5);
+        assert_eq!(stats.traffic[0], Traffic { taken: 2, added: 3 });
+        assert_eq!(stats.traffic[2], Traffic::default());
+        assert_eq!(stats.cost, 7);
+
+        let err = problem("move 1 from 2 to 1\nmove 9 from 1 to 2")
+            .execute_with_stats(Crane::CrateMover9000, &CostModel::default())
+            .unwrap_err();
+        assert_eq!(err.index(), 1);
+    }
+
+    #[test]
+    fn test_huge_costs() {
+        let costs = CostModel {
+            per_move: u64::MAX,
+            per_crate: u64::MAX,
+        };
+        assert_eq!(costs.cost(0), u128::from(u64::MAX));
+        assert_eq!(costs.cost(3), 4 * u128::from(u64::MAX));
+
+        let (_, stats) = problem("move 1 from 2 to 1\nswap 1 3")
+            .execute_with_stats(Crane::CrateMover9000, &costs)
+            .unwrap();
+        assert_eq!(stats.crates_moved, 5);
+        assert_eq!(stats.cost, 7 * u128::from(u64::MAX));
+        assert!(stats.to_string().contains("cost 129127208515966861305"));
+    }
+}
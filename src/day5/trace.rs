@@ -0,0 +1,150 @@
+use super::{Crane, ExecError, Problem, Snapshot};
+use bincode::Options;
+use serde::{Deserialize, Serialize};
+
+/// Everything a visualiser needs to replay a solve: the problem, the crane
+/// and the stacks after each instruction, starting from the drawing as step 0.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct Trace {
+    pub crane: Crane,
+    pub problem: Problem,
+    pub snapshots: Vec<Snapshot>,
+}
+
+impl Trace {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("traces always serialize")
+    }
+
+    pub fn to_json_pretty(&self) -> String {
+        serde_json::to_string_pretty(self).expect("traces always serialize")
+    }
+
+    pub fn from_json(json: &str) -> Result<Trace, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// A compact binary encoding, with variable-length integers, for traces
+    /// too long to ship as JSON.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        bincode::DefaultOptions::new()
+            .serialize(self)
+            .expect("traces always serialize")
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Trace, bincode::Error> {
+        bincode::DefaultOptions::new().deserialize(bytes)
+    }
+}
+
+impl Problem {
+    /// Runs every instruction with `crane`, keeping a snapshot of each state.
+    pub fn trace(&self, crane: Crane) -> Result<Trace, ExecError> {
+        let mut sim = self.simulate(crane);
+        let mut snapshots = vec![sim.snapshot()];
+        for snapshot in &mut sim {
+            snapshots.push(snapshot?);
+        }
+        Ok(Trace {
+            crane,
+            problem: self.clone(),
+            snapshots,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day5::{problem, stacks_of, Instruction, MAX_STATEMENTS};
+
+    fn script() -> Problem {
+        problem(
+            "move 1 from 2 to 1
+move 3 from 1 to 3
+swap 1 2
+repeat 2 {
+    reverse 2 on 3
+}",
+        )
+    }
+
+    #[test]
+    fn test_json_shape() {
+        let problem = script();
+        let json = serde_json::to_value(&problem).unwrap();
+        assert_eq!(json["stacks"][1], serde_json::json!(["M", "C", "D"]));
+        assert_eq!(
+            json["instructions"][0],
+            serde_json::json!({"move": {"count": 1, "from": 2, "to": 1}})
+        );
+        assert_eq!(
+            json["instructions"][2],
+            serde_json::json!({"swap": {"a": 1, "b": 2}})
+        );
+        assert_eq!(
+            json["instructions"][3]["repeat"]["body"][0],
+            serde_json::json!({"reverse": {"count": 2, "stack": 3}})
+        );
+
+        let instruction: Instruction =
+            serde_json::from_str(r#"{"move": {"count": 3, "from": 1, "to": 2}}"#).unwrap();
+        assert_eq!(instruction.to_string(), "move 3 from 1 to 2");
+
+        let err = serde_json::from_str::<Problem>(r#"{"stacks": [["A#"]], "instructions": []}"#)
+            .unwrap_err();
+        assert!(err.to_string().contains("'A#' is not a valid crate label"));
+    }
+
+    #[test]
+    fn test_trace() {
+        let problem = script();
+        let trace = problem.trace(Crane::CrateMover9000).unwrap();
+        assert_eq!(trace.snapshots.len(), problem.instructions.len() + 1);
+        assert_eq!(trace.snapshots[0].stacks, problem.stacks);
+        assert_eq!(trace.snapshots[2].stacks, stacks_of(&["", "MC", "PDNZ"]));
+        assert_eq!(
+            trace.snapshots.last().unwrap().stacks,
+            problem.solve(Crane::CrateMover9000).stacks
+        );
+
+        assert_eq!(Trace::from_json(&trace.to_json()).unwrap(), trace);
+        assert_eq!(Trace::from_json(&trace.to_json_pretty()).unwrap(), trace);
+        assert_eq!(Trace::from_bytes(&trace.to_bytes()).unwrap(), trace);
+        assert!(trace.to_bytes().len() < trace.to_json().len());
+    }
+
+    #[test]
+    fn test_deserialize_limits() {
+        let json = r#"{"stacks": [["A"]], "instructions": [
+            {"repeat": {"times": 1000000000000, "body": [{"swap": {"a": 1, "b": 1}}]}}
+        ]}"#;
+        let err = serde_json::from_str::<Problem>(json).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("the script runs past 10000000 statements"));
+
+        let json = r#"{"stacks": [["A"]], "instructions": [
+            {"repeat": {"times": 2, "body": [{"repeat": {"times": 3, "body": []}}]}}
+        ]}"#;
+        let err = serde_json::from_str::<Problem>(json).unwrap_err();
+        assert!(err.to_string().contains("a 'repeat' has nothing to repeat"));
+
+        // Built by hand, so never checked on the way in
+        let mut trace = script().trace(Crane::CrateMover9000).unwrap();
+        trace.problem.instructions.push(Instruction::Repeat {
+            times: MAX_STATEMENTS,
+            body: vec!["swap 1 1".parse().unwrap()],
+        });
+        assert!(Trace::from_json(&trace.to_json()).is_err());
+        assert!(Trace::from_bytes(&trace.to_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_trace_error() {
+        let mut problem = script();
+        problem.instructions[1] = "move 9 from 1 to 3".parse().unwrap();
+        let err = problem.trace(Crane::CrateMover9000).unwrap_err();
+        assert_eq!(err.index(), 1);
+    }
+}
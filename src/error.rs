@@ -0,0 +1,135 @@
+use std::error::Error;
+use std::fmt;
+
+/// A problem with a puzzle input, pointing at the offending text.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseError {
+    pub day: u8,
+    /// 1-based line number within the whole input
+    pub line: usize,
+    /// 1-based column of the first offending character
+    pub column: usize,
+    /// The full source line the error was found on
+    pub text: String,
+    /// Width of the offending text, in characters
+    pub width: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    /// Builds an error for `offending`, which should be a slice of `line`.
+    /// The line number starts at 1; callers that see the whole input fix it up
+    /// with `at_line`.
+    pub fn new(day: u8, line: &str, offending: &str, message: impl Into<String>) -> ParseError {
+        let offset = offset_in(line, offending);
+        ParseError {
+            day,
+            line: 1,
+            column: line[..offset].chars().count() + 1,
+            text: line.to_owned(),
+            width: offending.chars().count(),
+            message: message.into(),
+        }
+    }
+
+    pub fn at_line(mut self, line: usize) -> ParseError {
+        self.line = line;
+        self
+    }
+
+    /// Moves an error found in `part`, a slice of `line`, onto the whole line.
+    pub fn in_line(mut self, line: &str, part: &str) -> ParseError {
+        self.column += line[..offset_in(line, part)].chars().count();
+        self.text = line.to_owned();
+        self
+    }
+
+    /// Renders the error with the source line and a caret underline.
+    pub fn render(&self) -> String {
+        let gutter = " ".repeat(self.line.to_string().len());
+        format!(
+            "error: {}\n{} --> day {}, line {}, column {}\n{} |\n{} | {}\n{} | {}{}",
+            self.message,
+            gutter,
+            self.day,
+            self.line,
+            self.column,
+            gutter,
+            self.line,
+            self.text,
+            gutter,
+            " ".repeat(self.column - 1),
+            "^".repeat(self.width.max(1)),
+        )
+    }
+}
+
+/// Byte offset of `part` within `line`, falling back to a search when it isn't
+/// actually a slice of it.
+fn offset_in(line: &str, part: &str) -> usize {
+    (part.as_ptr() as usize)
+        .checked_sub(line.as_ptr() as usize)
+        .filter(|offset| offset + part.len() <= line.len())
+        .or_else(|| line.find(part))
+        .unwrap_or(0)
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "day {}, line {}, column {}: {}",
+            self.day, self.line, self.column, self.message
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_from_slice() {
+        let line = "move 1 from x to 3";
+        let err = ParseError::new(5, line, &line[12..13], "bad stack");
+
+        assert_eq!(err.column, 13);
+        assert_eq!(err.width, 1);
+        assert_eq!(err.line, 1);
+        assert_eq!(err.at_line(4).line, 4);
+    }
+
+    #[test]
+    fn test_in_line() {
+        let line = "    move 1 from x to 3 # indented";
+        let part = &line[4..22];
+        let err = ParseError::new(5, part, &part[12..13], "bad stack").in_line(line, part);
+
+        assert_eq!(err.column, 17);
+        assert_eq!(err.text, line);
+    }
+
+    #[test]
+    fn test_render() {
+        let line = "2-4,6-q";
+        let err = ParseError::new(4, line, &line[6..], "'q' is not a section number").at_line(12);
+
+        let expected = "error: 'q' is not a section number
+   --> day 4, line 12, column 7
+   |
+12 | 2-4,6-q
+   |       ^";
+        assert_eq!(err.render(), expected);
+    }
+
+    #[test]
+    fn test_render_end_of_line() {
+        let line = "A";
+        let err = ParseError::new(2, line, &line[1..], "expected a second column");
+
+        assert_eq!(err.column, 2);
+        assert!(err.render().ends_with("1 | A\n  |  ^"));
+    }
+}
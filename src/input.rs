@@ -0,0 +1,217 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use tracing::{debug, trace};
+
+/// Directory holding puzzle inputs, laid out as `dayN/input.txt` or `dayN.txt`.
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+#[derive(Debug)]
+pub enum InputError {
+    Stdin(io::Error),
+    NotFound {
+        day: u8,
+        tried: Vec<(PathBuf, io::Error)>,
+    },
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Stdin(e) => write!(f, "Failed to read input from stdin: {}", e),
+            InputError::NotFound { day, tried } => {
+                write!(f, "Failed to read input for day {}, tried:", day)?;
+                for (path, e) in tried {
+                    write!(f, "\n  {} ({})", path.display(), e)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for InputError {}
+
+/// Paths searched for `day`'s input when none is given on the command line,
+/// in the order they're tried.
+pub fn candidate_paths(day: u8, input_dir: Option<&Path>) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    if let Some(dir) = input_dir {
+        paths.push(dir.join(format!("day{}", day)).join("input.txt"));
+        paths.push(dir.join(format!("day{}.txt", day)));
+    }
+    paths.push(
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join(format!("day{}", day))
+            .join("input.txt"),
+    );
+    paths
+}
+
+/// Reads the input for `day`. An explicit `arg` is either a path or `-` for
+/// stdin; otherwise `AOC_INPUT_DIR` and then the crate's `src/dayN/input.txt`
+/// are searched.
+pub fn load_input(day: u8, arg: Option<&str>) -> Result<String, InputError> {
+    let input_dir = std::env::var_os(INPUT_DIR_VAR).map(PathBuf::from);
+    load_input_from(day, arg, input_dir.as_deref())
+}
+
+fn load_input_from(
+    day: u8,
+    arg: Option<&str>,
+    input_dir: Option<&Path>,
+) -> Result<String, InputError> {
+    let paths = match arg {
+        Some("-") => {
+            let mut buf = String::new();
+            io::stdin()
+                .read_to_string(&mut buf)
+                .map_err(InputError::Stdin)?;
+            debug!(day, bytes = buf.len(), "read input from stdin");
+            return Ok(buf);
+        }
+        Some(path) => vec![PathBuf::from(path)],
+        None => candidate_paths(day, input_dir),
+    };
+
+    let mut tried = Vec::new();
+    for path in paths {
+        match fs::read_to_string(&path) {
+            Ok(input) => {
+                debug!(day, path = %path.display(), bytes = input.len(), "read input");
+                return Ok(input);
+            }
+            Err(e) => {
+                trace!(day, path = %path.display(), error = %e, "no input here");
+                tried.push((path, e));
+            }
+        }
+    }
+    Err(InputError::NotFound { day, tried })
+}
+
+/// Iterates over the lines of `input` with their 1-based line numbers.
+/// Accepts `\n` or `\r\n` endings, trims trailing whitespace from every line
+/// and drops blank lines at the end of the input.
+pub fn lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    let last = input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .last()
+        .map_or(0, |(i, _)| i + 1);
+
+    input
+        .lines()
+        .map(str::trim_end)
+        .enumerate()
+        .take(last)
+        .map(|(i, line)| (i + 1, line))
+}
+
+/// Groups the normalised lines of `input` into blocks separated by one or
+/// more blank lines.
+pub fn blocks(input: &str) -> Vec<Vec<(usize, &str)>> {
+    let mut blocks = Vec::new();
+    let mut current = Vec::new();
+    for (number, line) in lines(input) {
+        if line.is_empty() {
+            if !current.is_empty() {
+                blocks.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push((number, line));
+        }
+    }
+    if !current.is_empty() {
+        blocks.push(current);
+    }
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("advent2022-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_lines_crlf() {
+        let lines = lines("A Y\r\nB X\r\nC Z\r\n").collect::<Vec<_>>();
+        assert_eq!(lines, vec![(1, "A Y"), (2, "B X"), (3, "C Z")]);
+    }
+
+    #[test]
+    fn test_lines_trailing_whitespace() {
+        let lines = lines("    [D]    \n 1 \t\n\n\n  \n").collect::<Vec<_>>();
+        assert_eq!(lines, vec![(1, "    [D]"), (2, " 1")]);
+
+        assert_eq!(super::lines("").count(), 0);
+        assert_eq!(super::lines("\n\r\n").count(), 0);
+    }
+
+    #[test]
+    fn test_blocks() {
+        let input = "\r\n1000\r\n2000\r\n\r\n   \r\n3000\r\n\r\n";
+        let blocks = blocks(input);
+        assert_eq!(
+            blocks,
+            vec![vec![(2, "1000"), (3, "2000")], vec![(6, "3000")]]
+        );
+    }
+
+    #[test]
+    fn test_candidate_paths() {
+        let paths = candidate_paths(4, Some(Path::new("/inputs")));
+        assert_eq!(paths[0], Path::new("/inputs/day4/input.txt"));
+        assert_eq!(paths[1], Path::new("/inputs/day4.txt"));
+        assert!(paths[2].ends_with("src/day4/input.txt"));
+        assert_eq!(paths.len(), 3);
+
+        assert_eq!(candidate_paths(4, None).len(), 1);
+    }
+
+    #[test]
+    fn test_default_input() {
+        // Every solved day ships its input alongside the code
+        let input = load_input_from(1, None, None).unwrap();
+        assert!(!input.is_empty());
+    }
+
+    #[test]
+    fn test_input_dir() {
+        let dir = scratch_dir("input-dir");
+        fs::write(dir.join("day7.txt"), "hello").unwrap();
+
+        let input = load_input_from(7, None, Some(&dir)).unwrap();
+        assert_eq!(input, "hello");
+    }
+
+    #[test]
+    fn test_explicit_path() {
+        let dir = scratch_dir("explicit");
+        let path = dir.join("custom.txt");
+        fs::write(&path, "A Y").unwrap();
+
+        let input = load_input_from(2, path.to_str(), Some(Path::new("/nonexistent"))).unwrap();
+        assert_eq!(input, "A Y");
+    }
+
+    #[test]
+    fn test_not_found_names_every_path() {
+        let err = load_input_from(42, None, Some(Path::new("/nonexistent"))).unwrap_err();
+        let message = err.to_string();
+
+        assert!(message.starts_with("Failed to read input for day 42"));
+        for path in candidate_paths(42, Some(Path::new("/nonexistent"))) {
+            assert!(message.contains(&path.display().to_string()));
+        }
+    }
+}
@@ -0,0 +1,14 @@
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod input;
+pub mod logging;
+pub mod runner;
+
+mod error;
+mod solution;
+
+pub use error::ParseError;
+pub use solution::Solution;
@@ -0,0 +1,84 @@
+use std::env;
+use std::io::{self, IsTerminal};
+use tracing::Level;
+
+/// Counts and strips the `-v`, `-vv` and `--verbose` flags from `args`,
+/// returning the verbosity and the arguments that are left.
+pub fn take_verbosity(args: impl IntoIterator<Item = String>) -> (u8, Vec<String>) {
+    let mut verbosity = 0u8;
+    let mut rest = Vec::new();
+    for arg in args {
+        match arg.as_str() {
+            "--verbose" => verbosity = verbosity.saturating_add(1),
+            flag if flag.len() > 1
+                && flag.starts_with('-')
+                && flag[1..].chars().all(|c| c == 'v') =>
+            {
+                verbosity = verbosity.saturating_add((flag.len() - 1) as u8)
+            }
+            _ => rest.push(arg),
+        }
+    }
+    (verbosity, rest)
+}
+
+/// The most detailed events shown at `verbosity`: warnings by default,
+/// parsing and solving steps with `-v`, every line and move with `-vv`.
+pub fn level(verbosity: u8) -> Level {
+    match verbosity {
+        0 => Level::WARN,
+        1 => Level::DEBUG,
+        _ => Level::TRACE,
+    }
+}
+
+/// Sends events at `verbosity` and above to stderr, keeping stdout for answers.
+pub fn init(verbosity: u8) {
+    // Only the first call in a process takes effect
+    let _ = tracing_subscriber::fmt()
+        .with_max_level(level(verbosity))
+        .with_writer(io::stderr)
+        .with_ansi(io::stderr().is_terminal())
+        .without_time()
+        .try_init();
+}
+
+/// Sets up logging from the process's command line, returning the arguments
+/// after the program name with the verbosity flags removed.
+pub fn init_from_args() -> Vec<String> {
+    let (verbosity, rest) = take_verbosity(env::args().skip(1));
+    init(verbosity);
+    rest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_take_verbosity() {
+        assert_eq!(
+            take_verbosity(args(&["input.txt"])),
+            (0, args(&["input.txt"]))
+        );
+        assert_eq!(
+            take_verbosity(args(&["-v", "--day", "5"])),
+            (1, args(&["--day", "5"]))
+        );
+        assert_eq!(take_verbosity(args(&["-vv", "-"])), (2, args(&["-"])));
+        assert_eq!(take_verbosity(args(&["-v", "--verbose"])), (2, args(&[])));
+        assert_eq!(take_verbosity(args(&["-", "-x"])), (0, args(&["-", "-x"])));
+    }
+
+    #[test]
+    fn test_level() {
+        assert_eq!(level(0), Level::WARN);
+        assert_eq!(level(1), Level::DEBUG);
+        assert_eq!(level(2), Level::TRACE);
+        assert_eq!(level(9), Level::TRACE);
+    }
+}
@@ -0,0 +1,146 @@
+use crate::day1::Day1;
+use crate::day2::Day2;
+use crate::day3::Day3;
+use crate::day4::Day4;
+use crate::day5::Day5;
+use crate::{ParseError, Solution};
+use std::fmt;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+pub const DAYS: [u8; 5] = [1, 2, 3, 4, 5];
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const BOTH: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn from_number(n: &str) -> Option<Part> {
+        match n {
+            "1" => Some(Part::One),
+            "2" => Some(Part::Two),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PartResult {
+    pub part: Part,
+    pub answer: String,
+    pub elapsed: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct DayResult {
+    pub day: u8,
+    pub parse_elapsed: Duration,
+    pub parts: Vec<PartResult>,
+}
+
+impl fmt::Display for DayResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Day {} (parsed in {:.2?})", self.day, self.parse_elapsed)?;
+        for part in &self.parts {
+            writeln!(
+                f,
+                "  Part {}: {} ({:.2?})",
+                part.part, part.answer, part.elapsed
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses `input` once and runs the requested parts of `S`, timing each step.
+pub fn run<S: Solution>(input: &str, parts: &[Part]) -> Result<DayResult, ParseError> {
+    let start = Instant::now();
+    let parsed = S::parse(input)?;
+    let parse_elapsed = start.elapsed();
+    debug!(day = S::DAY, elapsed = ?parse_elapsed, "parsed input");
+
+    let parts = parts
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            let answer = match part {
+                Part::One => S::part1(&parsed).to_string(),
+                Part::Two => S::part2(&parsed).to_string(),
+            };
+            let elapsed = start.elapsed();
+            debug!(day = S::DAY, %part, %answer, ?elapsed, "solved");
+            PartResult {
+                part,
+                answer,
+                elapsed,
+            }
+        })
+        .collect();
+
+    Ok(DayResult {
+        day: S::DAY,
+        parse_elapsed,
+        parts,
+    })
+}
+
+/// Runs the solver for `day`, or `None` if that day hasn't been solved.
+pub fn run_day(day: u8, input: &str, parts: &[Part]) -> Option<Result<DayResult, ParseError>> {
+    match day {
+        1 => Some(run::<Day1>(input, parts)),
+        2 => Some(run::<Day2>(input, parts)),
+        3 => Some(run::<Day3>(input, parts)),
+        4 => Some(run::<Day4>(input, parts)),
+        5 => Some(run::<Day5>(input, parts)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_day() {
+        let result = run_day(2, "A Y\nB X\nC Z", &Part::BOTH).unwrap().unwrap();
+        assert_eq!(result.day, 2);
+
+        let answers = result
+            .parts
+            .iter()
+            .map(|p| (p.part, p.answer.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(answers, vec![(Part::One, "15"), (Part::Two, "12")]);
+    }
+
+    #[test]
+    fn test_run_single_part() {
+        let result = run_day(2, "A Y\nB X\nC Z", &[Part::Two]).unwrap().unwrap();
+        assert_eq!(result.parts.len(), 1);
+        assert_eq!(result.parts[0].answer, "12");
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = run_day(2, "A Y\nB Q", &Part::BOTH).unwrap().unwrap_err();
+        assert_eq!((err.day, err.line, err.column), (2, 2, 3));
+    }
+
+    #[test]
+    fn test_unknown_day() {
+        assert!(run_day(25, "", &Part::BOTH).is_none());
+    }
+}
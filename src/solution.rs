@@ -0,0 +1,15 @@
+use crate::ParseError;
+use std::fmt::Display;
+
+/// A single day's puzzle: parse the raw input once, then answer both parts from it.
+pub trait Solution {
+    const DAY: u8;
+
+    type Input;
+    type Answer1: Display;
+    type Answer2: Display;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+    fn part1(input: &Self::Input) -> Self::Answer1;
+    fn part2(input: &Self::Input) -> Self::Answer2;
+}